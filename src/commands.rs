@@ -4,40 +4,67 @@ pub mod compile;
 pub mod config;
 pub mod create;
 pub mod edit;
+pub mod export;
 pub mod helpers;
+pub mod import;
+mod includes;
 pub mod info;
+pub mod launcher;
 pub mod lilypond;
 pub mod list;
+pub mod lsp;
 pub mod open;
+pub mod parts;
 mod patterns;
+pub mod preview;
+pub mod render;
 mod scores;
 pub mod sketch;
+pub mod stats;
 pub mod table;
 pub mod templates;
+pub mod textedit;
+pub mod transpose;
+pub mod tui;
 pub mod update_path;
 pub mod update_version;
+pub mod watch;
 
 use std::fmt::{Display, Formatter, Result};
 use std::io::{stdin, stdout, Write};
+use std::str::FromStr;
 
-use clap::Subcommand;
 use clap::ValueEnum;
+use clap::{Args, Subcommand};
 use convert_case::{Case::Kebab, Casing};
 use serde::Deserialize;
 use shellexpand::tilde;
 
 use crate::activate::Shell;
+use crate::commands::compile::FileFormat;
 use crate::commands::helpers::Helper;
+use crate::commands::render::GraphicsProtocol;
 use crate::commands::templates::Template;
+use crate::commands::transpose::Instrument;
 use crate::config::Config;
 
 #[derive(Clone, Debug, Deserialize, ValueEnum)]
 pub enum ConfigKey {
+    Clef,
     Composer,
+    Editor,
+    EditorIsTerminal,
     Instrument,
+    Key,
+    Paper,
+    PdfViewer,
     PDFSDirectory,
+    Pickup,
+    PointAndClick,
     ScoresDirectory,
+    Tempo,
     Template,
+    Time,
 }
 
 impl Display for ConfigKey {
@@ -52,6 +79,18 @@ pub enum ScoreFileType {
     Both,
     Lilypond,
     Pdf,
+    Png,
+    Svg,
+    Midi,
+    Eps,
+}
+
+#[derive(Clone, Debug, Deserialize, ValueEnum)]
+pub enum OutputFormat {
+    Csv,
+    Json,
+    Markdown,
+    Plain,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, ValueEnum)]
@@ -75,6 +114,10 @@ pub enum LilypondCommand {
         /// List only stable or unstbale versions
         #[arg(long)]
         stability: Option<VersionStability>,
+
+        /// Print output as csv, json, markdown, or plain text
+        #[arg(long)]
+        format: Option<OutputFormat>,
     },
 
     /// List all versions available for download
@@ -84,13 +127,101 @@ pub enum LilypondCommand {
         /// List only stable or unstbale versions
         #[arg(long)]
         stability: Option<VersionStability>,
+
+        /// Print output as csv, json, markdown, or plain text
+        #[arg(long)]
+        format: Option<OutputFormat>,
     },
 }
 
+#[derive(Args)]
+pub struct CreateArgs {
+    /// Prompt for the score settings when omitted
+    pub title: Option<String>,
+
+    #[arg(long)]
+    pub subtitle: Option<String>,
+
+    #[arg(long)]
+    pub composer: Option<String>,
+
+    #[arg(long)]
+    pub arranger: Option<String>,
+
+    /// Built-in or custom template name
+    #[arg(long, value_parser = Template::from_str)]
+    pub template: Option<Template>,
+
+    #[arg(long)]
+    pub instrument: Option<String>,
+
+    /// Key (e.g. "d minor", "bf major", "fs dorian")
+    #[arg(long)]
+    pub key: Option<String>,
+
+    /// Time signature (e.g. "3/4")
+    #[arg(long)]
+    pub time: Option<String>,
+
+    /// Tempo as beats per minute or text (e.g. "120", "Allegro 4 = 132")
+    #[arg(long)]
+    pub tempo: Option<String>,
+
+    #[arg(long)]
+    pub clef: Option<String>,
+
+    /// Pickup duration (e.g. "4", "8.")
+    #[arg(long)]
+    pub pickup: Option<String>,
+
+    /// Paper size (e.g. "a4", "letter")
+    #[arg(long)]
+    pub paper: Option<String>,
+
+    /// Open for editing after creating
+    #[arg(long)]
+    pub edit: bool,
+
+    /// Overwrite an existing score with the same title
+    #[arg(long)]
+    pub force: bool,
+
+    /// Print the files that would be created without writing them
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Stamp and compile using the specified lilypond version
+    #[arg(long)]
+    pub lilypond_version: Option<String>,
+
+    #[arg(long)]
+    pub scores_directory: Option<String>,
+
+    #[arg(long)]
+    pub pdfs_directory: Option<String>,
+}
+
 #[derive(Subcommand)]
 pub enum TemplateCommand {
     /// Show the template contents
-    Show { template: Template },
+    Show {
+        #[arg(value_parser = Template::from_str)]
+        template: Template,
+    },
+
+    /// Compile the template with sample values and open the pdf
+    Preview {
+        #[arg(value_parser = Template::from_str)]
+        template: Template,
+
+        /// Save the first page as a png to the specified path instead
+        #[arg(long)]
+        png: Option<String>,
+
+        /// Stamp and compile using the specified lilypond version
+        #[arg(long)]
+        lilypond_version: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -99,6 +230,31 @@ pub enum HelperCommand {
     Show { helper: Helper },
 }
 
+#[derive(Subcommand)]
+pub enum SketchCommand {
+    /// Save the current sketch to scores/<composer>/sketches
+    Save,
+
+    /// List saved sketches
+    List,
+
+    /// Open a saved sketch for editing
+    Open { sketch: String },
+
+    /// Turn a saved sketch into a score
+    Promote {
+        sketch: String,
+
+        /// Title of the score (defaults to the sketch title)
+        #[arg(long)]
+        title: Option<String>,
+
+        /// Create the score from a template, keeping the sketch's music
+        #[arg(long, value_parser = Template::from_str)]
+        template: Option<Template>,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum Command {
     #[command(hide = true)]
@@ -110,7 +266,7 @@ pub enum Command {
         version: Option<String>,
     },
 
-    /// Remove pdf(s)
+    /// Remove pdf(s) and other compiled outputs
     Clean {
         search_terms: Vec<String>,
 
@@ -126,6 +282,10 @@ pub enum Command {
         #[arg(long)]
         all: bool,
 
+        /// Output formats to remove, comma-separated (default: all)
+        #[arg(long, value_delimiter = ',')]
+        format: Vec<FileFormat>,
+
         #[arg(long)]
         scores_directory: Option<String>,
 
@@ -133,7 +293,7 @@ pub enum Command {
         pdfs_directory: Option<String>,
     },
 
-    /// Create pdf(s)
+    /// Create pdf(s) and other output formats
     Compile {
         search_terms: Vec<String>,
 
@@ -161,6 +321,14 @@ pub enum Command {
 
         #[arg(long)]
         force: bool,
+
+        /// Output formats, comma-separated or repeated (default: pdf)
+        #[arg(long, value_delimiter = ',')]
+        format: Vec<FileFormat>,
+
+        /// Png resolution in dpi
+        #[arg(long)]
+        resolution: Option<u16>,
     },
 
     /// Display config
@@ -177,30 +345,34 @@ pub enum Command {
         /// Display the config file path
         #[arg(long)]
         path: bool,
+
+        /// Print output as csv, json, markdown, or plain text
+        #[arg(long)]
+        format: Option<OutputFormat>,
     },
 
     /// Create new score template
-    Create {
-        title: String,
+    Create(Box<CreateArgs>),
 
-        #[arg(long)]
-        subtitle: Option<String>,
+    /// Open <score> in editor and pdf viewer, recompiling on file changes
+    Edit {
+        search_terms: String,
 
+        /// Match search terms against artist field only
         #[arg(long)]
-        composer: Option<String>,
+        artist: bool,
 
+        /// Match search terms against title field only
         #[arg(long)]
-        arranger: Option<String>,
-
-        #[arg(long, value_enum)]
-        template: Option<Template>,
+        title: bool,
 
+        /// Use all matching scores without prompting
         #[arg(long)]
-        instrument: Option<String>,
+        all: bool,
 
-        /// Open for editing after creating
+        /// Serve a live svg preview instead of opening the pdf viewer
         #[arg(long)]
-        edit: bool,
+        preview: bool,
 
         /// Compile using the specified lilypond version
         #[arg(long)]
@@ -213,9 +385,9 @@ pub enum Command {
         pdfs_directory: Option<String>,
     },
 
-    /// Open <score> in editor and pdf viewer, recompiling on file changes
-    Edit {
-        search_terms: String,
+    /// Export a chord chart for a lead sheet or form chart <score>
+    Export {
+        search_term: String,
 
         /// Match search terms against artist field only
         #[arg(long)]
@@ -225,19 +397,48 @@ pub enum Command {
         #[arg(long)]
         title: bool,
 
-        /// Use all matching scores without prompting
-        #[arg(long)]
-        all: bool,
+        /// Export as ChordPro
+        #[arg(long, required_unless_present = "text")]
+        chordpro: bool,
 
-        /// Compile using the specified lilypond version
+        /// Export as plain text with chords above the lyrics
+        #[arg(long, conflicts_with = "chordpro")]
+        text: bool,
+
+        /// Write to the specified file instead of stdout
         #[arg(long)]
-        lilypond_version: Option<String>,
+        output: Option<String>,
 
         #[arg(long)]
         scores_directory: Option<String>,
+    },
 
+    /// Convert a MusicXML, ABC, MIDI or ChordPro file into a new score
+    Import {
+        file: String,
+
+        /// Parse <file> as ChordPro and create a lead sheet
         #[arg(long)]
-        pdfs_directory: Option<String>,
+        chordpro: bool,
+
+        /// Use instead of the title found in the file
+        #[arg(long)]
+        title: Option<String>,
+
+        /// Use instead of the composer found in the file
+        #[arg(long)]
+        composer: Option<String>,
+
+        /// Overwrite an existing score with the same title
+        #[arg(long)]
+        force: bool,
+
+        /// Convert and stamp using the specified lilypond version
+        #[arg(long)]
+        lilypond_version: Option<String>,
+
+        #[arg(long)]
+        scores_directory: Option<String>,
     },
 
     /// Display <score> info
@@ -256,6 +457,10 @@ pub enum Command {
         #[arg(long)]
         all: bool,
 
+        /// Display the first page of the score in the terminal
+        #[arg(long)]
+        preview: bool,
+
         #[arg(long)]
         scores_directory: Option<String>,
     },
@@ -292,6 +497,10 @@ pub enum Command {
 
         #[arg(long)]
         pdfs_directory: Option<String>,
+
+        /// Print output as csv, json, markdown, or plain text
+        #[arg(long)]
+        format: Option<OutputFormat>,
     },
 
     /// Open score(s)
@@ -324,21 +533,224 @@ pub enum Command {
     Templates {
         #[command(subcommand)]
         command: Option<TemplateCommand>,
+
+        /// Print output as csv, json, markdown, or plain text
+        #[arg(long)]
+        format: Option<OutputFormat>,
     },
 
     /// List helper files
     Helpers {
         #[command(subcommand)]
         command: Option<HelperCommand>,
+
+        /// Print output as csv, json, markdown, or plain text
+        #[arg(long)]
+        format: Option<OutputFormat>,
     },
 
-    /// Open temporary sketch file
+    /// Open a temporary sketch, or save, list, open and promote sketches
     Sketch {
+        #[command(subcommand)]
+        command: Option<SketchCommand>,
+
+        /// Built-in or custom template name
+        #[arg(long, value_parser = Template::from_str)]
+        template: Option<Template>,
+
+        /// Reopen the most recent sketch
+        #[arg(long, conflicts_with = "template")]
+        last: bool,
+
         /// Compile using the specified lilypond version
         #[arg(long)]
         lilypond_version: Option<String>,
     },
 
+    /// Display repository statistics
+    Stats {
+        search_terms: Vec<String>,
+
+        /// Match search terms against artist field only
+        #[arg(long)]
+        artist: bool,
+
+        /// Match search terms against title field only
+        #[arg(long)]
+        title: bool,
+
+        #[arg(long)]
+        scores_directory: Option<String>,
+
+        #[arg(long)]
+        pdfs_directory: Option<String>,
+    },
+
+    /// Generate and compile a part for each instrument in <score>
+    Parts {
+        search_term: String,
+
+        /// Match search terms against artist field only
+        #[arg(long)]
+        artist: bool,
+
+        /// Match search terms against title field only
+        #[arg(long)]
+        title: bool,
+
+        #[arg(long)]
+        scores_directory: Option<String>,
+
+        #[arg(long)]
+        pdfs_directory: Option<String>,
+    },
+
+    /// Serve a live svg preview of <score>, reloading on file changes
+    Preview {
+        search_term: String,
+
+        /// Match search terms against artist field only
+        #[arg(long)]
+        artist: bool,
+
+        /// Match search terms against title field only
+        #[arg(long)]
+        title: bool,
+
+        /// Port to serve the preview on (default 8000)
+        #[arg(long)]
+        port: Option<u16>,
+
+        /// Compile using the specified lilypond version
+        #[arg(long)]
+        lilypond_version: Option<String>,
+
+        #[arg(long)]
+        scores_directory: Option<String>,
+    },
+
+    /// Run a language server for scores over stdio
+    Lsp {
+        /// Compile using the specified lilypond version
+        #[arg(long)]
+        lilypond_version: Option<String>,
+
+        #[arg(long)]
+        scores_directory: Option<String>,
+    },
+
+    /// Render a lilypond fragment (or stdin) inline in the terminal
+    Render {
+        fragment: Option<String>,
+
+        /// Terminal graphics protocol (detected from the terminal by default)
+        #[arg(long, value_enum)]
+        protocol: Option<GraphicsProtocol>,
+
+        /// Compile using the specified lilypond version
+        #[arg(long)]
+        lilypond_version: Option<String>,
+
+        #[arg(long)]
+        scores_directory: Option<String>,
+    },
+
+    /// Open a point-and-click textedit:// link in the editor
+    Textedit { uri: String },
+
+    /// Create a transposed variant of <score> and compile it
+    Transpose {
+        search_term: String,
+
+        /// Key (e.g. "d", "bf minor") or interval (e.g. "M2", "down m3", "-2")
+        #[arg(long, allow_hyphen_values = true)]
+        to: Option<String>,
+
+        /// Transpose to written pitch for a transposing instrument
+        #[arg(long, value_enum)]
+        instrument: Option<Instrument>,
+
+        /// Match search terms against artist field only
+        #[arg(long)]
+        artist: bool,
+
+        /// Match search terms against title field only
+        #[arg(long)]
+        title: bool,
+
+        /// Overwrite an existing transposed variant
+        #[arg(long)]
+        force: bool,
+
+        #[arg(long)]
+        scores_directory: Option<String>,
+
+        #[arg(long)]
+        pdfs_directory: Option<String>,
+    },
+
+    /// Browse, compile and edit scores in a full-screen terminal UI
+    Tui {
+        search_terms: Vec<String>,
+
+        /// Match search terms against artist field only
+        #[arg(long)]
+        artist: bool,
+
+        /// Match search terms against title field only
+        #[arg(long)]
+        title: bool,
+
+        /// Compile using the specified lilypond version
+        #[arg(long)]
+        lilypond_version: Option<String>,
+
+        #[arg(long)]
+        scores_directory: Option<String>,
+
+        #[arg(long)]
+        pdfs_directory: Option<String>,
+
+        /// Output formats, comma-separated or repeated (default: pdf)
+        #[arg(long, value_delimiter = ',')]
+        format: Vec<FileFormat>,
+
+        /// Png resolution in dpi
+        #[arg(long)]
+        resolution: Option<u16>,
+    },
+
+    /// Keep pdf(s) up to date, recompiling on file changes
+    Watch {
+        search_terms: Vec<String>,
+
+        /// Match search terms against artist field only
+        #[arg(long)]
+        artist: bool,
+
+        /// Match search terms against title field only
+        #[arg(long)]
+        title: bool,
+
+        /// Compile using the specified lilypond version
+        #[arg(long)]
+        lilypond_version: Option<String>,
+
+        #[arg(long)]
+        scores_directory: Option<String>,
+
+        #[arg(long)]
+        pdfs_directory: Option<String>,
+
+        /// Output formats, comma-separated or repeated (default: pdf)
+        #[arg(long, value_delimiter = ',')]
+        format: Vec<FileFormat>,
+
+        /// Png resolution in dpi
+        #[arg(long)]
+        resolution: Option<u16>,
+    },
+
     /// Update lilypond version for score(s)
     UpdateVersion {
         search_terms: Vec<String>,
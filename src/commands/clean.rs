@@ -1,7 +1,8 @@
 use std::fs::remove_file;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use super::scores::{get_found_pdfs, get_selected_items};
+use super::compile::FileFormat;
+use super::scores::{get_found_outputs, get_selected_items};
 use crate::commands::received_confirmation;
 
 fn remove_score(path: &Path) {
@@ -19,27 +20,41 @@ pub fn main(
     search_artist: bool,
     search_title: bool,
     use_all_matches: bool,
+    formats: &[FileFormat],
     scores_directory: &Option<String>,
     pdfs_directory: &Option<String>,
 ) {
     if search_terms.is_empty()
         && !received_confirmation(
-            "Are you sure you want to remove all pdfs? [y/n]",
+            "Are you sure you want to remove all compiled outputs? [y/n]",
         )
     {
         return;
     };
 
-    let matching_pdfs = get_found_pdfs(
-        search_terms,
-        search_artist,
-        search_title,
-        scores_directory,
-        pdfs_directory,
-    );
+    let formats = if formats.is_empty() {
+        &FileFormat::ALL[..]
+    } else {
+        formats
+    };
+
+    let matching_outputs: Vec<PathBuf> = formats
+        .iter()
+        .flat_map(|format| {
+            get_found_outputs(
+                search_terms,
+                search_artist,
+                search_title,
+                scores_directory,
+                pdfs_directory,
+                *format,
+            )
+        })
+        .collect();
 
-    if !use_all_matches && matching_pdfs.len() > 1 {
-        if let Ok(selected_items) = get_selected_items(&matching_pdfs, true) {
+    if !use_all_matches && matching_outputs.len() > 1 {
+        if let Ok(selected_items) = get_selected_items(&matching_outputs, true)
+        {
             for item in &selected_items {
                 let path = item.output().to_string();
                 let path = Path::new(&path);
@@ -47,7 +62,7 @@ pub fn main(
             }
         }
     } else {
-        for score in matching_pdfs {
+        for score in matching_outputs {
             remove_score(score.as_path());
         }
     }
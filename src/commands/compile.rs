@@ -5,7 +5,8 @@ use std::println;
 use std::process::Command;
 use std::time::SystemTime;
 
-use glob::glob;
+use clap::ValueEnum;
+use glob::{glob, Pattern};
 
 use super::get_pdfs_directory_from_arg;
 use super::get_scores_directory_from_arg;
@@ -16,6 +17,139 @@ use super::scores::get_score_ly_file;
 use super::scores::get_selected_items;
 use super::scores::search;
 
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum FileFormat {
+    Pdf,
+    Png,
+    Svg,
+    Midi,
+    Eps,
+}
+
+impl FileFormat {
+    pub const ALL: [Self; 5] =
+        [Self::Pdf, Self::Png, Self::Svg, Self::Midi, Self::Eps];
+
+    pub const fn get_extension(self) -> &'static str {
+        match self {
+            Self::Pdf => "pdf",
+            Self::Png => "png",
+            Self::Svg => "svg",
+            Self::Midi => "midi",
+            Self::Eps => "eps",
+        }
+    }
+
+    /// Pdfs go in the pdfs directory itself, everything else in a
+    /// subdirectory named after the format (e.g. "pdfs/png").
+    pub fn get_output_directory(self, pdfs_directory: &str) -> String {
+        match self {
+            Self::Pdf => pdfs_directory.to_string(),
+            _ => format!("{pdfs_directory}/{}", self.get_extension()),
+        }
+    }
+
+    /// Every `self` output of `input_file`: `{stem}.{extension}`, or its
+    /// numbered pages (e.g. "{stem}-1.svg" or "{stem}-page1.png") when there
+    /// is a first page, so that a score named "{stem}-2" isn't mistaken for
+    /// a page.
+    pub fn get_output_files(
+        self,
+        input_file: &Path,
+        pdfs_directory: &str,
+    ) -> Vec<PathBuf> {
+        let stem = input_file
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .expect("Failed to get input file stem.");
+        let directory = self.get_output_directory(pdfs_directory);
+        let extension = self.get_extension();
+
+        let page_prefixes: Vec<String> =
+            [format!("{stem}-"), format!("{stem}-page")]
+                .into_iter()
+                .filter(|prefix| {
+                    Path::new(&format!("{directory}/{prefix}1.{extension}"))
+                        .exists()
+                })
+                .collect();
+
+        let is_page = |name: &str| {
+            page_prefixes.iter().any(|prefix| {
+                name.strip_prefix(prefix.as_str()).is_some_and(|number| {
+                    !number.is_empty()
+                        && number.chars().all(|char| char.is_ascii_digit())
+                })
+            })
+        };
+
+        glob(&format!(
+            "{}/{}*.{extension}",
+            Pattern::escape(&directory),
+            Pattern::escape(stem)
+        ))
+        .expect("Failed to read glob pattern")
+        .flatten()
+        .filter(|file| {
+            file.file_stem()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name == stem || is_page(name))
+        })
+        .collect()
+    }
+
+    /// Whether `input_file` has a `self` output newer than itself.
+    pub fn is_compiled(self, input_file: &Path, pdfs_directory: &str) -> bool {
+        self.get_output_files(input_file, pdfs_directory)
+            .iter()
+            .any(|output_file| {
+                is_compiled(&input_file.to_path_buf(), output_file)
+            })
+    }
+
+    /// Lilypond's own flags for producing `self`, with `resolution` in dpi
+    /// for png.
+    fn get_arguments(self, resolution: Option<u16>) -> Vec<String> {
+        match self {
+            Self::Pdf => vec![],
+            Self::Png => {
+                let mut arguments = vec!["--png".to_string()];
+
+                if let Some(resolution) = resolution {
+                    arguments.push(format!("-dresolution={resolution}"));
+                }
+
+                arguments
+            }
+            Self::Svg => vec!["-dbackend=svg".to_string()],
+            // Midi is only written for scores with a \midi block.
+            Self::Midi => vec!["-dno-print-pages".to_string()],
+            Self::Eps => vec!["--eps".to_string()],
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct CompileOptions<'a> {
+    /// Defaults to pdf only.
+    pub formats: Vec<FileFormat>,
+    pub resolution: Option<u16>,
+    pub arguments: &'a [&'a str],
+    pub force: bool,
+    /// Leaves reporting to the caller instead of printing lilypond's output.
+    pub quiet: bool,
+}
+
+impl CompileOptions<'_> {
+    pub fn get_formats(&self) -> &[FileFormat] {
+        if self.formats.is_empty() {
+            &[FileFormat::Pdf]
+        } else {
+            &self.formats
+        }
+    }
+}
+
 fn get_modified(file: &PathBuf) -> Option<SystemTime> {
     metadata(file).map_or_else(
         |_| None,
@@ -85,74 +219,157 @@ fn get_lilypond_version(
             return version;
         }
 
-        println!("Unrecognized lilypond version: \"{version}\"");
-        println!("Attempting to use veresion specified in the input file...");
+        eprintln!("Unrecognized lilypond version: \"{version}\"");
+        eprintln!("Attempting to use veresion specified in the input file...");
     }
 
     get_lilypond_version_from_file(file)
 }
 
-pub fn compile_input_file(
-    input_file: &PathBuf,
+/// Path to `binary` (e.g. "lilypond" or "musicxml2ly") in the managed
+/// installation for `version`, or the bare name to run it from PATH.
+pub fn get_binary_command(binary: &str, version: String) -> String {
+    get_binary(version).map_or_else(
+        || binary.to_string(),
+        |command| format!("{command}/{binary}"),
+    )
+}
+
+pub fn get_lilypond_command(
+    file: &str,
     lilypond_version: &Option<String>,
-    scores_directory: &Option<String>,
-    pdfs_directory: &Option<String>,
-    force: bool,
-) {
-    let pdfs_directory = &get_pdfs_directory_from_arg(pdfs_directory);
-    let scores_directory = &get_scores_directory_from_arg(scores_directory);
+) -> String {
+    let version = get_lilypond_version(file, lilypond_version);
 
-    if let Some(file) = input_file.to_str() {
-        let err = "Failed to get input file stem.";
+    get_binary_command("lilypond", version)
+}
 
-        let output_file_pattern = format!(
-            "{pdfs_directory}/{}*.pdf",
-            input_file.file_stem().expect(err).to_str().expect(err)
-        );
+/// Defines `thoth-point-and-click` in the `guile-user` module, which
+/// `helpers/settings.ily` imports and checks before turning point-and-click
+/// off.
+pub const POINT_AND_CLICK_ARGUMENTS: [&str; 2] =
+    ["-e", "(define-public thoth-point-and-click #t)"];
 
-        for entry in glob(&output_file_pattern)
-            .expect("Failed to read glob pattern")
-            .flatten()
-        {
-            if !force && is_compiled(input_file, &entry) {
-                return;
+/// Runs lilypond on `input_file` with any extra `arguments` (e.g.
+/// "-dbackend=svg"), returning its log output.
+pub fn run_lilypond(
+    input_file: &Path,
+    lilypond_version: &Option<String>,
+    arguments: &[&str],
+    scores_directory: &str,
+    output_directory: &str,
+) -> Result<String, String> {
+    let file = input_file
+        .to_str()
+        .expect("Failed to parse input file path.");
+    let command = get_lilypond_command(file, lilypond_version);
+
+    match Command::new(command)
+        .args(["--include", scores_directory])
+        .args(["--output", output_directory])
+        .args(arguments)
+        .arg(file)
+        .output()
+    {
+        Ok(output) => {
+            let log = String::from_utf8_lossy(&output.stderr).to_string();
+
+            if output.status.success() {
+                Ok(log)
+            } else {
+                Err(log)
             }
         }
+        Err(error) => Err(format!("Error: {error}")),
+    }
+}
 
-        let version = get_lilypond_version(file, lilypond_version);
+fn compile_format(
+    input_file: &Path,
+    format: FileFormat,
+    lilypond_version: &Option<String>,
+    scores_directory: &str,
+    pdfs_directory: &str,
+    options: &CompileOptions,
+) -> Result<String, String> {
+    if !options.force && format.is_compiled(input_file, pdfs_directory) {
+        return Ok(String::new());
+    }
 
-        let command = get_binary(version).map_or_else(
-            || "lilypond".to_string(),
-            |command| format!("{command}/lilypond"),
-        );
+    let output_directory = format.get_output_directory(pdfs_directory);
+    create_dir_all(&output_directory)
+        .expect("Failed to create output directory.");
 
-        match Command::new(command)
-            .args(["--include", scores_directory])
-            .args(["--output", pdfs_directory])
-            .arg(file)
-            .output()
-        {
-            Ok(output) => {
-                let err = "Failed to parse input file path.";
-
-                if output.status.success() {
-                    let file_name = input_file
-                        .file_stem()
-                        .expect(err)
-                        .to_str()
-                        .expect(err);
-                    println!("Compiled {pdfs_directory}/{file_name}.pdf");
-                } else {
-                    io::stdout()
-                        .write_all(output.stderr.as_ref())
-                        .expect("Failed to print lilypond command output.");
-                }
-            }
-            Err(error) => {
-                println!("Error: {error}");
+    let format_arguments = format.get_arguments(options.resolution);
+    let mut arguments = options.arguments.to_vec();
+    arguments.extend(format_arguments.iter().map(String::as_str));
+
+    let err = "Failed to get input file stem.";
+    let file_name = input_file.file_stem().expect(err).to_str().expect(err);
+    let extension = format.get_extension();
+
+    let result = run_lilypond(
+        input_file,
+        lilypond_version,
+        &arguments,
+        scores_directory,
+        &output_directory,
+    );
+
+    if options.quiet {
+        return result;
+    }
+
+    match &result {
+        Ok(_) => {
+            if matches!(format, FileFormat::Midi)
+                && format
+                    .get_output_files(input_file, pdfs_directory)
+                    .is_empty()
+            {
+                println!(
+                    "No midi output for {file_name}. Does the score have a \\midi block?"
+                );
+            } else {
+                println!(
+                    "Compiled {output_directory}/{file_name}.{extension}"
+                );
             }
         }
+        Err(output) => io::stdout()
+            .write_all(output.as_bytes())
+            .expect("Failed to print lilypond command output."),
     }
+
+    result
+}
+
+/// Compiles `input_file` to each of `options.formats`, with lilypond's output
+/// for all of them, or for the first that fails.
+pub fn compile_input_file(
+    input_file: &Path,
+    lilypond_version: &Option<String>,
+    scores_directory: &Option<String>,
+    pdfs_directory: &Option<String>,
+    options: &CompileOptions,
+) -> Result<String, String> {
+    let pdfs_directory = &get_pdfs_directory_from_arg(pdfs_directory);
+    let scores_directory = &get_scores_directory_from_arg(scores_directory);
+    options
+        .get_formats()
+        .iter()
+        .try_fold(String::new(), |mut log, format| {
+            log.push_str(&compile_format(
+                input_file,
+                *format,
+                lilypond_version,
+                scores_directory,
+                pdfs_directory,
+                options,
+            )?);
+
+            Ok(log)
+        })
 }
 
 pub fn main(
@@ -163,7 +380,7 @@ pub fn main(
     lilypond_version: &Option<String>,
     scores_directory: &Option<String>,
     pdfs_directory: &Option<String>,
-    force: bool,
+    options: &CompileOptions,
 ) {
     {
         let pdfs_directory = get_pdfs_directory_from_arg(pdfs_directory);
@@ -184,12 +401,12 @@ pub fn main(
                 let score = item.output().to_string();
 
                 if let Some(input_file) = get_score_ly_file(&score) {
-                    compile_input_file(
+                    let _ = compile_input_file(
                         &PathBuf::from(input_file),
                         lilypond_version,
                         scores_directory,
                         pdfs_directory,
-                        force,
+                        options,
                     );
                 }
             }
@@ -202,12 +419,12 @@ pub fn main(
                 .to_string();
 
             if let Some(input_file) = get_score_ly_file(&score) {
-                compile_input_file(
+                let _ = compile_input_file(
                     &PathBuf::from(input_file),
                     lilypond_version,
                     scores_directory,
                     pdfs_directory,
-                    force,
+                    options,
                 );
             }
         }
@@ -1,4 +1,4 @@
-use crate::commands::ConfigKey;
+use crate::commands::{ConfigKey, OutputFormat};
 use crate::config::Config;
 
 pub fn main(
@@ -6,6 +6,7 @@ pub fn main(
     path: bool,
     key: &Option<ConfigKey>,
     set: &Option<String>,
+    format: &Option<OutputFormat>,
 ) {
     if edit {
         Config::edit();
@@ -16,15 +17,15 @@ pub fn main(
             || {
                 Config::display_value(key);
             },
-            |value| Config::set_value(key, value.to_string()),
+            |value| Config::set_value(key, value.to_string(), format),
         );
     } else if let Some(set) = set {
         if let Some(key) = key {
-            Config::set_value(key, set.to_string());
+            Config::set_value(key, set.to_string(), format);
         } else {
             println!("Please specify the key to set.");
         }
     } else {
-        Config::display();
+        Config::display(format);
     }
 }
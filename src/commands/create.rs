@@ -1,23 +1,25 @@
-use std::fs::{create_dir_all, File};
+pub mod wizard;
+
+use std::fs::{create_dir_all, File, OpenOptions};
 use std::io::prelude::*;
 use std::path::Path;
 
 use super::add_value_to_string_if_some;
-use super::edit::edit_file;
-use super::scores::{get_temporary_ly_file, TEMPORARY_DIRECTORY};
+use super::edit::{edit_file, EditOptions};
+use super::lilypond::resolve_lilypond_version;
+use super::scores::get_new_sketch_directory;
 use crate::commands::edit;
-use crate::commands::templates::form::get_form_templates;
-use crate::commands::templates::lead::get_lead_templates;
-use crate::commands::templates::piano::get_piano_template;
-use crate::commands::templates::single::get_single_template;
+use crate::commands::templates::settings::{
+    parse_clef, parse_key, parse_paper, parse_pickup, parse_tempo, parse_time,
+    TemplateSettings,
+};
 use crate::commands::templates::{
-    Template,
-    Template::{Form, Lead, Piano, Single},
-    TemplateFile,
+    get_not_found_message, get_part_file_name, get_templates, print_templates,
+    Template, TemplateFile,
 };
 use crate::commands::{
     get_composer_from_arg, get_scores_directory_from_arg,
-    get_template_from_arg,
+    get_template_from_arg, received_confirmation,
 };
 use crate::config::Config;
 
@@ -28,6 +30,12 @@ pub struct ScoreFileSettings {
     pub arranger: Option<String>,
     pub template: Option<Template>,
     pub instrument: Option<String>,
+    pub key: Option<String>,
+    pub time: Option<String>,
+    pub tempo: Option<String>,
+    pub clef: Option<String>,
+    pub pickup: Option<String>,
+    pub paper: Option<String>,
 }
 
 impl Default for ScoreFileSettings {
@@ -39,37 +47,32 @@ impl Default for ScoreFileSettings {
             arranger: None,
             instrument: None,
             template: Some(Template::Piano),
+            key: None,
+            time: None,
+            tempo: None,
+            clef: None,
+            pickup: None,
+            paper: None,
         }
     }
 }
 
-fn get_templates(
-    title: &String,
-    subtitle: &Option<String>,
-    composer: &String,
-    arranger: &Option<String>,
-    instrument: &String,
-    template: &Template,
-) -> Vec<TemplateFile> {
-    match template {
-        Form => get_form_templates(title, subtitle, composer, arranger),
-        Lead => {
-            get_lead_templates(title, subtitle, composer, arranger, instrument)
-        }
-        Piano => get_piano_template(title, subtitle, composer, arranger),
-        Single => get_single_template(
-            title, subtitle, composer, arranger, instrument,
-        ),
-    }
+#[derive(Default)]
+pub struct CreateOptions {
+    pub edit: bool,
+    pub is_sketch: bool,
+    pub force: bool,
+    pub dry_run: bool,
 }
 
 pub fn create_file(
     template: TemplateFile,
     parent: &String,
     mut title: String,
+    overwrite: bool,
 ) -> String {
     if let Some(filename) = template.filename {
-        title = (*format!("{title}-{filename}.ily")).to_string();
+        title = format!("{title}-{}", get_part_file_name(&filename));
     } else {
         title = format!("{title}.ly");
     }
@@ -78,7 +81,13 @@ pub fn create_file(
     let path = Path::new(&filename);
     let file_display = path.display();
 
-    let mut file = match File::create(path) {
+    let file = if overwrite {
+        File::create(path)
+    } else {
+        OpenOptions::new().write(true).create_new(true).open(path)
+    };
+
+    let mut file = match file {
         Ok(file) => file,
         Err(message) => panic!("couldn't create {file_display}: {message}"),
     };
@@ -94,48 +103,181 @@ pub fn get_file_system_name(text: &str) -> String {
     text.replace(' ', "-").to_lowercase()
 }
 
+pub fn get_template_settings(
+    settings: &ScoreFileSettings,
+    lilypond_version: &Option<String>,
+) -> Result<TemplateSettings, String> {
+    let config = Config::from_config_file();
+
+    let lilypond_version = resolve_lilypond_version(lilypond_version)
+        .ok_or_else(|| {
+            lilypond_version.as_ref().map_or_else(
+                || {
+                    "Failed to find a lilypond version. Install one with \
+                    `thoth lilypond install` or use --lilypond-version."
+                        .to_string()
+                },
+                |version| {
+                    format!("Unrecognized lilypond version: \"{version}\"")
+                },
+            )
+        })?;
+
+    let tempo = settings
+        .tempo
+        .as_ref()
+        .or(config.tempo.as_ref())
+        .map(|tempo| parse_tempo(tempo))
+        .transpose()?;
+
+    let pickup = settings
+        .pickup
+        .as_ref()
+        .or(config.pickup.as_ref())
+        .map(|pickup| parse_pickup(pickup))
+        .transpose()?;
+
+    Ok(TemplateSettings {
+        title: settings.title.to_string(),
+        file_name: get_file_system_name(&settings.title),
+        subtitle: settings.subtitle.clone(),
+        composer: get_composer_from_arg(&settings.composer),
+        arranger: settings.arranger.clone(),
+        instrument: settings.instrument.as_ref().map_or_else(
+            || config.instrument.to_string(),
+            ToString::to_string,
+        ),
+        key: parse_key(settings.key.as_ref().unwrap_or(&config.key))?,
+        time: parse_time(settings.time.as_ref().unwrap_or(&config.time))?,
+        tempo,
+        clef: parse_clef(settings.clef.as_ref().unwrap_or(&config.clef))?,
+        pickup,
+        paper: parse_paper(settings.paper.as_ref().unwrap_or(&config.paper))?,
+        lilypond_version,
+    })
+}
+
+fn get_score_directory(
+    scores_directory: &str,
+    composer: &str,
+    title: &str,
+) -> String {
+    let composer_directory = get_file_system_name(composer);
+
+    format!("{scores_directory}/scores/{composer_directory}/{title}")
+}
+
+fn get_available_title(
+    scores_directory: &str,
+    composer: &str,
+    title: &str,
+) -> String {
+    let mut number = 2;
+
+    loop {
+        let available_title = format!("{title}-{number}");
+        let directory =
+            get_score_directory(scores_directory, composer, &available_title);
+
+        if !Path::new(&directory).exists() {
+            return available_title;
+        }
+
+        number += 1;
+    }
+}
+
+/// Refuses to write into an existing score directory unless `force` is set,
+/// offering a numbered title such as `title-2` instead.
+fn get_file_system_title(
+    scores_directory: &str,
+    composer: &str,
+    title: &str,
+    options: &CreateOptions,
+) -> Result<String, String> {
+    let directory = get_score_directory(scores_directory, composer, title);
+
+    if options.force || !Path::new(&directory).exists() {
+        return Ok(title.to_string());
+    }
+
+    let available_title =
+        get_available_title(scores_directory, composer, title);
+
+    if options.dry_run {
+        println!(
+            "\"{directory}\" already exists, using \"{available_title}\" instead."
+        );
+
+        return Ok(available_title);
+    }
+
+    if received_confirmation(&format!(
+        "\"{directory}\" already exists. Create \"{available_title}\" instead? [y/N]"
+    )) {
+        Ok(available_title)
+    } else {
+        Err("Score not created. Use --force to overwrite the existing score."
+            .to_string())
+    }
+}
+
 pub fn create_score(
     settings: &ScoreFileSettings,
+    lilypond_version: &Option<String>,
     scores_directory: &Option<String>,
-    is_sketch: bool,
-) -> Vec<String> {
-    let title = &settings.title;
-    let subtitle = &settings.subtitle;
+    options: &CreateOptions,
+) -> Result<Vec<String>, String> {
+    let mut template_settings =
+        get_template_settings(settings, lilypond_version)?;
     let composer = get_composer_from_arg(&settings.composer);
-    let arranger = &settings.arranger;
     let template = get_template_from_arg(&settings.template);
-    let instrument = &settings.instrument;
-    let file_system_title = get_file_system_name(title);
+    let mut file_system_title = get_file_system_name(&settings.title);
 
-    let parent = if is_sketch {
-        TEMPORARY_DIRECTORY.to_string()
+    let parent = if options.is_sketch {
+        get_new_sketch_directory()
     } else {
         let scores_directory = get_scores_directory_from_arg(scores_directory);
-        let composer_directory = get_file_system_name(&composer);
-        format!("{scores_directory}/scores/{composer_directory}/{file_system_title}")
-    };
 
-    create_dir_all(&parent).expect("Failed to create score parent directory.");
-    let config = Config::from_config_file();
+        file_system_title = get_file_system_title(
+            &scores_directory,
+            &composer,
+            &file_system_title,
+            options,
+        )?;
 
-    let instrument = if let Some(instrument) = instrument {
-        instrument
-    } else {
-        &config.instrument
+        get_score_directory(&scores_directory, &composer, &file_system_title)
     };
 
-    let templates = get_templates(
-        title, subtitle, &composer, arranger, instrument, &template,
-    );
+    template_settings.file_name.clone_from(&file_system_title);
+
+    let templates =
+        get_templates(&template_settings, &template, scores_directory)
+            .ok_or_else(|| get_not_found_message(&template))?;
 
+    if options.dry_run {
+        println!("Would create the following files in {parent}:\n");
+        print_templates(&templates, &file_system_title);
+
+        return Ok(vec![]);
+    }
+
+    create_dir_all(&parent).expect("Failed to create score parent directory.");
+
+    let overwrite = options.is_sketch || options.force;
     let mut files = Vec::new();
 
     for template in templates {
-        let file = create_file(template, &parent, file_system_title.clone());
+        let file = create_file(
+            template,
+            &parent,
+            file_system_title.clone(),
+            overwrite,
+        );
         files.push(file);
     }
 
-    files
+    Ok(files)
 }
 
 pub fn print_score_info(
@@ -146,9 +288,8 @@ pub fn print_score_info(
     instrument: &Option<String>,
     template: &Template,
 ) {
-    let mut score_info = format!(
-        "Created score for \"{title}\" using {template:?} template:\n"
-    );
+    let mut score_info =
+        format!("Created score for \"{title}\" using {template} template:\n");
 
     score_info = add_value_to_string_if_some(score_info, "Subtitle", subtitle);
     score_info = add_value_to_string_if_some(
@@ -165,8 +306,7 @@ pub fn print_score_info(
 
 pub fn main(
     settings: &ScoreFileSettings,
-    edit: bool,
-    is_sketch: bool,
+    options: &CreateOptions,
     lilypond_version: &Option<String>,
     scores_directory: &Option<String>,
     pdfs_directory: &Option<String>,
@@ -192,7 +332,27 @@ pub fn main(
         &config.composer
     };
 
-    let files = create_score(settings, scores_directory, is_sketch);
+    let files = match create_score(
+        settings,
+        lilypond_version,
+        scores_directory,
+        options,
+    ) {
+        Ok(files) => files,
+        Err(message) => {
+            println!("{message}");
+
+            return;
+        }
+    };
+
+    if options.dry_run {
+        return;
+    }
+
+    let CreateOptions {
+        edit, is_sketch, ..
+    } = *options;
 
     print_score_info(
         title, subtitle, composer, arranger, instrument, template,
@@ -202,26 +362,47 @@ pub fn main(
         println!("Opening for editing...");
     }
 
-    for file in files {
+    for file in &files {
         println!("{file}");
     }
 
     if edit {
         if is_sketch {
+            let Some(ly_file) =
+                files.iter().find(|file| file.ends_with(".ly"))
+            else {
+                return;
+            };
+
             edit_file(
-                &get_temporary_ly_file(),
-                is_sketch,
+                ly_file,
+                &EditOptions {
+                    is_sketch,
+                    ..Default::default()
+                },
                 lilypond_version,
                 &None,
                 &None,
             );
         } else {
+            let search_term = files
+                .first()
+                .and_then(|file| Path::new(file).file_stem())
+                .and_then(|stem| stem.to_str())
+                .map_or_else(
+                    || get_file_system_name(title),
+                    ToString::to_string,
+                );
+
             edit::main(
-                &get_file_system_name(title),
+                &search_term,
                 false,
                 false,
                 true,
-                is_sketch,
+                &EditOptions {
+                    is_sketch,
+                    ..Default::default()
+                },
                 lilypond_version,
                 scores_directory,
                 pdfs_directory,
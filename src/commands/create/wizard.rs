@@ -0,0 +1,222 @@
+use std::collections::BTreeSet;
+use std::io::{stdin, stdout, Write};
+use std::path::Path;
+
+use owo_colors::OwoColorize;
+
+use crate::commands::create::{
+    get_file_system_name, CreateOptions, ScoreFileSettings,
+};
+use crate::commands::get_scores_directory_from_arg;
+use crate::commands::info::get_composition_metadata;
+use crate::commands::scores::{get_score_ly_file, search};
+use crate::commands::templates::{get_template_descriptions, Template};
+use crate::config::Config;
+
+/// Returns `None` when stdin is closed, so that the wizard can be aborted.
+fn read_line(message: &str, default: Option<&str>) -> Option<String> {
+    match default {
+        Some(default) if !default.is_empty() => {
+            print!("{message} [{}]: ", default.yellow());
+        }
+        _ => print!("{message}: "),
+    }
+
+    stdout().flush().expect("Failed to flush stdout.");
+
+    let mut response = String::new();
+
+    if stdin()
+        .read_line(&mut response)
+        .expect("Failed to read input.")
+        == 0
+    {
+        println!();
+        return None;
+    }
+
+    let response = response.trim().to_string();
+
+    if response.is_empty() {
+        Some(default.unwrap_or_default().to_string())
+    } else {
+        Some(response)
+    }
+}
+
+fn read_optional_line(
+    message: &str,
+    default: &Option<String>,
+) -> Option<Option<String>> {
+    let response = read_line(message, default.as_deref())?;
+
+    Some(if response.is_empty() {
+        None
+    } else {
+        Some(response)
+    })
+}
+
+fn read_confirmation(message: &str, default: bool) -> Option<bool> {
+    let options = if default { "Y/n" } else { "y/N" };
+    let response = read_line(&format!("{message} [{options}]"), None)?;
+
+    Some(match response.to_lowercase().as_str() {
+        "y" | "yes" => true,
+        "n" | "no" => false,
+        _ => default,
+    })
+}
+
+fn get_composer_history(scores_directory: &Option<String>) -> Vec<String> {
+    let mut composers = BTreeSet::new();
+
+    for score in search(&vec![], false, false, scores_directory) {
+        let Some(ly_file) = score
+            .to_str()
+            .and_then(|score| get_score_ly_file(&score.to_string()))
+        else {
+            continue;
+        };
+
+        if let Some(composer) = get_composition_metadata(&ly_file).composer {
+            composers.insert(composer);
+        }
+    }
+
+    composers.into_iter().collect()
+}
+
+fn select_from_list(
+    message: &str,
+    items: &[(String, String)],
+    default: &str,
+) -> Option<String> {
+    println!("{message}:");
+
+    for (index, (name, description)) in items.iter().enumerate() {
+        if description.is_empty() {
+            println!("  {}. {name}", index + 1);
+        } else {
+            println!("  {}. {name} {}", index + 1, description.dimmed());
+        }
+    }
+
+    read_line("Enter a number or name", Some(default)).map(|response| {
+        response
+            .parse::<usize>()
+            .ok()
+            .and_then(|number| items.get(number.wrapping_sub(1)))
+            .map_or(response, |(name, _)| name.to_string())
+    })
+}
+
+fn read_title() -> Option<String> {
+    loop {
+        let title = read_line("Title", None)?;
+
+        if title.is_empty() {
+            println!("Title is required.");
+        } else {
+            return Some(title);
+        }
+    }
+}
+
+fn read_template(
+    default: &Template,
+    scores_directory: &Option<String>,
+) -> Option<Template> {
+    let templates = get_template_descriptions(scores_directory);
+
+    loop {
+        let response =
+            select_from_list("Template", &templates, &default.to_string())?;
+        let template = Template::from(response.clone());
+
+        if templates.iter().any(|(name, _)| {
+            Template::from(name.to_string()).to_string()
+                == template.to_string()
+        }) {
+            return Some(template);
+        }
+
+        println!("Template \"{response}\" not found.");
+    }
+}
+
+fn get_score_directory(
+    scores_directory: &Option<String>,
+    composer: &str,
+    title: &str,
+) -> String {
+    let scores_directory = get_scores_directory_from_arg(scores_directory);
+    let composer = get_file_system_name(composer);
+    let title = get_file_system_name(title);
+
+    format!("{scores_directory}/scores/{composer}/{title}")
+}
+
+/// Prompts for the score settings, suggesting any values given as arguments or
+/// set in the config file as defaults.
+pub fn main(
+    settings: ScoreFileSettings,
+    options: &mut CreateOptions,
+    scores_directory: &Option<String>,
+) -> Option<ScoreFileSettings> {
+    let config = Config::from_config_file();
+
+    let mut title = read_title()?;
+    let subtitle = read_optional_line("Subtitle", &settings.subtitle)?;
+
+    let composers = get_composer_history(scores_directory);
+    let default_composer =
+        settings.composer.clone().unwrap_or(config.composer);
+
+    let composer = if composers.is_empty() {
+        read_line("Composer", Some(&default_composer))?
+    } else {
+        let composers: Vec<(String, String)> = composers
+            .into_iter()
+            .map(|composer| (composer, String::new()))
+            .collect();
+
+        select_from_list("Composer", &composers, &default_composer)?
+    };
+
+    loop {
+        let directory =
+            get_score_directory(scores_directory, &composer, &title);
+
+        if options.force || !Path::new(&directory).exists() {
+            break;
+        }
+
+        println!("\"{directory}\" already exists. Choose a different title.");
+        title = read_title()?;
+    }
+
+    let arranger = read_optional_line("Arranger", &settings.arranger)?;
+
+    let template = read_template(
+        settings.template.as_ref().unwrap_or(&config.template),
+        scores_directory,
+    )?;
+
+    let instrument = read_line(
+        "Instrument",
+        Some(settings.instrument.as_ref().unwrap_or(&config.instrument)),
+    )?;
+
+    options.edit = read_confirmation("Open for editing?", options.edit)?;
+
+    Some(ScoreFileSettings {
+        title,
+        subtitle,
+        composer: Some(composer),
+        arranger,
+        template: Some(template),
+        instrument: Some(instrument),
+        ..settings
+    })
+}
@@ -1,264 +1,208 @@
-// use std::fs::create_dir_all;
-// use std::fs::remove_dir_all;
-// use std::fs::rename;
-// use std::fs::File;
-// use std::io::{BufRead, BufReader};
+use std::fs::{create_dir_all, remove_dir_all, write};
 use std::path::{Path, PathBuf};
-use std::process::Command;
-
-// use chrono::offset::Local;
-use glob::glob;
-use watchexec::Watchexec;
-use watchexec_events::filekind::{DataChange, FileEventKind, ModifyKind};
-use watchexec_events::Tag;
-use watchexec_signals::Signal;
-
-use super::compile::compile_input_file;
-use super::scores::get_selected_items;
-// use crate::commands::create::get_file_system_name;
+use std::thread::spawn;
+use std::time::SystemTime;
+
+use super::compile::{
+    compile_input_file, CompileOptions, POINT_AND_CLICK_ARGUMENTS,
+};
+use super::get_scores_directory_from_arg;
+use super::launcher::{
+    open_in_editor, open_in_pdf_viewer, uses_terminal_editor,
+};
+use super::preview::{print_preview_url, serve_score, DEFAULT_PORT};
+use super::scores::{get_selected_items, TEMPORARY_DIRECTORY};
+use super::sketch::{is_modified_since, save_sketch};
+use super::watch::watch_scores;
 use crate::commands::patterns::get_score_file;
-// use crate::commands::scores::get_temporary_ly_file;
-use crate::commands::scores::TEMPORARY_DIRECTORY;
 use crate::commands::scores::{get_score_ly_file, search};
 use crate::config::Config;
 
-fn get_ily_files(pattern: &str) -> Vec<String> {
-    glob(pattern)
-        .expect("")
-        .flatten()
-        .map(|path| {
-            path.to_str()
-                .expect("Failed to parse .ily file path.")
-                .to_string()
-        })
-        .collect()
-}
-
-fn get_watched_files(file: &String) -> Vec<String> {
-    let parent = Path::new(file)
-        .parent()
-        .expect("Failed to get watched file's parent path.");
-    let score_ily_files_pattern = format!(
-        "{}/**/*.ily",
-        parent.to_str().expect("Failed to get included .ily files.")
-    );
-    let mut watched_files = get_ily_files(&score_ily_files_pattern);
-    let scores_directory = Config::get_scores_directory();
-    let helper_ily_files_pattern = format!("{scores_directory}/helpers/*.ily");
-    let mut helper_ily_files = get_ily_files(&helper_ily_files_pattern);
-
-    watched_files.append(&mut helper_ily_files);
-    watched_files.push(file.to_string());
+fn exit_sketch(directory: &Path, save: bool) {
+    if save {
+        save_sketch(directory);
+    }
 
-    watched_files
+    let _ = remove_dir_all(directory);
 }
 
-// fn exit_sketch(save: bool) {
-//     if save {
-//         let file =
-//             File::open(get_temporary_ly_file()).expect("file not found");
-//         let buf_reader = BufReader::new(file);
-//         let lines: Vec<String> = buf_reader
-//             .lines()
-//             .collect::<eyre::Result<_, _>>()
-//             .expect("Failed to read file.");
-//         let config = Config::from_config_file();
-//         let mut composer = config.composer;
-//         let mut title = "Sketch".to_string();
-
-//         for line in lines {
-//             let composer_line = "  composer = ";
-//             let title_line = "  title = ";
-
-//             if line.starts_with(composer_line) {
-//                 composer = get_file_system_name(
-//                     &line.replace(composer_line, "").replace('"', ""),
-//                 );
-//             }
-
-//             if line.starts_with(title_line) {
-//                 title = get_file_system_name(
-//                     &line.replace(title_line, "").replace('"', ""),
-//                 );
-//             }
-//         }
-
-//         let sketches_directory =
-//             format!("{}/scores/{composer}/sketches", config.scores_directory);
-
-//         if create_dir_all(&sketches_directory).is_ok() {
-//             let saved_file_path = format!(
-//                 "{sketches_directory}/{}-{title}.ly",
-//                 Local::now().format("%Y-%m-%d_%H:%M:%S")
-//             );
-
-//             if let Err(message) =
-//                 rename(get_temporary_ly_file(), saved_file_path)
-//             {
-//                 println!("{message}");
-//             }
-//         }
-//     }
-
-//     let _ = remove_dir_all(TEMPORARY_DIRECTORY);
-// }
-
-#[tokio::main]
-pub async fn watch(file: &Path, _is_sketch: bool) -> eyre::Result<()> {
-    color_eyre::install()?;
-
-    let file = file
-        .to_str()
-        .expect("Faild to parse file name.")
-        .to_string();
-
-    let watched_files = get_watched_files(&file);
-
-    let watchexec = Watchexec::new(|mut action| {
-        let config = Config::from_config_file();
-
-        let events = action.events.iter().filter(|event| {
-            event.tags.iter().any(|tag| match tag {
-                Tag::FileEventKind(FileEventKind::Modify(
-                    ModifyKind::Data(data_change),
-                )) => {
-                    matches!(data_change, DataChange::Content)
-                }
-                _ => false,
-            })
-        });
-
-        for event in events {
-            for path in event.paths() {
-                match path.0.parent() {
-                    Some(directory) => {
-                        let Some(directory) = directory.to_str() else {
-                            eprintln!(
-                                "Error: Failed to get watched directory."
-                            );
-
-                            continue;
-                        };
-
-                        let stuff = glob(&format!("{directory}/*.ly"));
+/// Extra lilypond arguments for builds in edit mode.
+fn get_edit_arguments() -> &'static [&'static str] {
+    if Config::from_config_file().point_and_click {
+        &POINT_AND_CLICK_ARGUMENTS
+    } else {
+        &[]
+    }
+}
 
-                        for entry in stuff.expect("").flatten() {
-                            let Some(lilypond_file) = entry.to_str() else {
-                                eprintln!(
-                                    "Error: Failed to get lilypond file."
-                                );
+/// Where builds report while a terminal editor owns the screen.
+fn get_log_file(file: &Path) -> PathBuf {
+    let stem = file
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .expect("Failed to get score file name.");
 
-                                continue;
-                            };
+    PathBuf::from(format!("{TEMPORARY_DIRECTORY}/edit/{stem}.log"))
+}
 
-                            let scores_directory =
-                                config.scores_directory.clone();
-                            let pdfs_directory = config.pdfs_directory.clone();
+fn write_log(log_file: &Path, score: &Path, result: &Result<String, String>) {
+    let log = match result {
+        Ok(output) => format!("Compiled {}\n{output}", score.display()),
+        Err(output) => output.to_string(),
+    };
 
-                            match Command::new("lilypond")
-                                .args([
-                                    "--include".to_string(),
-                                    scores_directory,
-                                    "--output".to_string(),
-                                    pdfs_directory,
-                                    lilypond_file.to_string(),
-                                ])
-                                .output()
-                            {
-                                Ok(output) => {
-                                    let error_message = "Error: Failed to get output of lilypond command";
+    if let Some(directory) = log_file.parent() {
+        let _ = create_dir_all(directory);
+    }
 
-                                    if let Ok(stdout) =
-                                        String::from_utf8(output.stdout)
-                                    {
-                                        println!("{stdout}");
-                                    } else {
-                                        eprintln!("{error_message}");
-                                    }
+    let _ = write(log_file, log);
+}
 
-                                    if let Ok(stderr) =
-                                        String::from_utf8(output.stderr)
-                                    {
-                                        eprintln!("{stderr}");
-                                    } else {
-                                        eprintln!("{error_message}");
-                                    }
-                                }
-                                Err(message) => eprintln!("{message}"),
-                            };
-                        }
-                    }
-                    None => {
-                        eprintln!("Error: Failed to locate parent folder.");
-                    }
-                }
+pub fn watch(
+    file: &Path,
+    lilypond_version: &Option<String>,
+    scores_directory: &Option<String>,
+    pdfs_directory: &Option<String>,
+) -> eyre::Result<()> {
+    let scores_directory = get_scores_directory_from_arg(scores_directory);
+    let compile_scores_directory = Some(scores_directory.clone());
+    let lilypond_version = lilypond_version.clone();
+    let pdfs_directory = pdfs_directory.clone();
+    let arguments = get_edit_arguments();
+    let log_file = uses_terminal_editor().then(|| get_log_file(file));
+
+    watch_scores(vec![file.to_path_buf()], scores_directory, move |scores| {
+        for score in &scores {
+            let result = compile_input_file(
+                score,
+                &lilypond_version,
+                &compile_scores_directory,
+                &pdfs_directory,
+                &CompileOptions {
+                    arguments,
+                    force: true,
+                    quiet: log_file.is_some(),
+                    ..Default::default()
+                },
+            );
+
+            if let Some(log_file) = &log_file {
+                write_log(log_file, score, &result);
             }
         }
-
-        if action.signals().any(|signal| signal == Signal::Interrupt) {
-            action.quit();
-        }
-
-        action
-    })?;
-
-    watchexec.config.pathset(watched_files);
-    let _ = watchexec.main().await?;
-
-    Ok(())
+    })
 }
 
-pub fn open_file(file: &PathBuf) {
-    Command::new("open")
-        .arg(file)
-        .output()
-        .unwrap_or_else(|err| {
-            panic!("{}", format!("Failed to open file: {err}"))
-        });
+#[derive(Default)]
+pub struct EditOptions {
+    pub is_sketch: bool,
+    pub preview: bool,
 }
 
 pub fn edit_file(
     lilypond_file: &str,
-    is_sketch: bool,
+    options: &EditOptions,
     lilypond_version: &Option<String>,
     scores_directory: &Option<String>,
     pdfs_directory: &Option<String>,
 ) {
     let score_path = PathBuf::from(&lilypond_file);
+    let is_sketch = options.is_sketch;
+    let started = SystemTime::now();
+
+    let sketch_directory = score_path
+        .parent()
+        .expect("Failed to get sketch directory.")
+        .to_path_buf();
 
     let pdfs_directory = if is_sketch {
-        Some(TEMPORARY_DIRECTORY.to_string())
+        Some(sketch_directory.display().to_string())
     } else {
         pdfs_directory.to_owned()
     };
 
-    compile_input_file(
-        &score_path,
-        lilypond_version,
-        scores_directory,
-        &pdfs_directory,
-        false,
-    );
+    let watched_path = score_path.clone();
+    let lilypond_version = lilypond_version.clone();
+    let scores_directory = scores_directory.clone();
 
-    let err = "Failed to get score pdf file.";
-    let pdf_file = get_score_file(
-        &score_path
-            .file_stem()
-            .expect(err)
-            .to_str()
-            .expect(err)
-            .to_string(),
-        ".pdf",
-        scores_directory,
-        &pdfs_directory,
-    )
-    .expect(err);
+    let watch_score: Box<dyn FnOnce() + Send> = if options.preview {
+        let scores_directory =
+            get_scores_directory_from_arg(&scores_directory);
+        let quiet = uses_terminal_editor();
+
+        if quiet {
+            print_preview_url(&watched_path, DEFAULT_PORT);
+        }
+
+        Box::new(move || {
+            serve_score(
+                watched_path,
+                DEFAULT_PORT,
+                &lilypond_version,
+                scores_directory,
+                quiet,
+            );
+        })
+    } else {
+        let _ = compile_input_file(
+            &score_path,
+            &lilypond_version,
+            &scores_directory,
+            &pdfs_directory,
+            &CompileOptions {
+                arguments: get_edit_arguments(),
+                ..Default::default()
+            },
+        );
+
+        let err = "Failed to get score pdf file.";
+        let pdf_file = get_score_file(
+            &score_path
+                .file_stem()
+                .expect(err)
+                .to_str()
+                .expect(err)
+                .to_string(),
+            ".pdf",
+            &scores_directory,
+            &pdfs_directory,
+        )
+        .expect(err);
+
+        open_in_pdf_viewer(&pdf_file);
+
+        Box::new(move || {
+            watch(
+                &watched_path,
+                &lilypond_version,
+                &scores_directory,
+                &pdfs_directory,
+            )
+            .expect("Failed to open score for editing.");
+        })
+    };
 
-    for file in [&score_path, &pdf_file] {
-        open_file(file);
+    if uses_terminal_editor() {
+        if !options.preview {
+            println!(
+                "Writing build output to {}",
+                get_log_file(&score_path).display()
+            );
+        }
+
+        spawn(watch_score);
+        open_in_editor(&score_path, None);
+    } else {
+        open_in_editor(&score_path, None);
+        watch_score();
     }
 
-    watch(&score_path, is_sketch).expect("Failed to open score for editing.");
+    if is_sketch {
+        exit_sketch(
+            &sketch_directory,
+            is_modified_since(&sketch_directory, started),
+        );
+    }
 }
 
 pub fn main(
@@ -266,7 +210,7 @@ pub fn main(
     search_artist: bool,
     search_title: bool,
     use_all_matches: bool,
-    is_sketch: bool,
+    options: &EditOptions,
     lilypond_version: &Option<String>,
     scores_directory: &Option<String>,
     pdfs_directory: &Option<String>,
@@ -288,7 +232,7 @@ pub fn main(
                 if let Some(ly_file) = get_score_ly_file(&score) {
                     edit_file(
                         &ly_file,
-                        is_sketch,
+                        options,
                         lilypond_version,
                         scores_directory,
                         pdfs_directory,
@@ -304,7 +248,7 @@ pub fn main(
             if let Some(ly_file) = get_score_ly_file(&score) {
                 edit_file(
                     &ly_file,
-                    is_sketch,
+                    options,
                     lilypond_version,
                     scores_directory,
                     pdfs_directory,
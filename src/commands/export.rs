@@ -0,0 +1,48 @@
+pub mod chordpro;
+
+use std::fs::write;
+
+use self::chordpro::{get_chart, ChartFormat};
+use super::scores::get_selected_ly_file;
+
+pub fn main(
+    search_term: &String,
+    search_artist: bool,
+    search_title: bool,
+    is_chordpro: bool,
+    output: &Option<String>,
+    scores_directory: &Option<String>,
+) {
+    let Some(ly_file) = get_selected_ly_file(
+        search_term,
+        search_artist,
+        search_title,
+        scores_directory,
+    ) else {
+        println!("No score found matching \"{search_term}\".");
+        return;
+    };
+
+    let format = if is_chordpro {
+        ChartFormat::ChordPro
+    } else {
+        ChartFormat::Text
+    };
+
+    let chart = match get_chart(&ly_file, format) {
+        Ok(chart) => chart,
+        Err(message) => {
+            println!("{message}");
+            return;
+        }
+    };
+
+    if let Some(output) = output {
+        match write(output, chart) {
+            Ok(()) => println!("Exported {output}"),
+            Err(err) => println!("Failed to write {output} ({err})"),
+        }
+    } else {
+        print!("{chart}");
+    }
+}
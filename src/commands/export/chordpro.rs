@@ -0,0 +1,517 @@
+use std::fs::read_to_string;
+use std::path::Path;
+
+use glob::glob;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use titlecase::titlecase;
+
+use crate::commands::info::get_composition_metadata;
+
+static VARIABLE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?m)^\s*([A-Za-z][\w-]*)\s*=\s*\\(chordmode|chords|lyricmode)\s*\{",
+    )
+    .expect("Failed to create variable regex.")
+});
+
+static CHORD: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^([a-g])(ss|ff|s|f|x)?(?:\d+\.*(?:\*\d+(?:/\d+)?)?)?(?::([^/\s^]*))?(?:\^[^/\s]*)?(?:/\+?([a-g])(ss|ff|s|f)?)?$",
+    )
+    .expect("Failed to create chord regex.")
+});
+
+static REST: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^[rR](?:\d+\.*(?:\*\d+(?:/\d+)?)?)?$")
+        .expect("Failed to create rest regex.")
+});
+
+static DURATION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\d+\.*$").expect("Failed to create duration regex.")
+});
+
+static TOKEN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#""(?:[^"\\]|\\.)*"|\S+"#)
+        .expect("Failed to create token regex.")
+});
+
+static SECTION_PREFIXES: [&str; 4] =
+    ["changes_", "chords_", "lyrics_", "words_"];
+
+#[derive(Clone, Copy)]
+pub enum ChartFormat {
+    ChordPro,
+    Text,
+}
+
+struct Variable {
+    name: String,
+    mode: String,
+    body: String,
+}
+
+struct Section {
+    name: String,
+    chords: Vec<Vec<String>>,
+    lyrics: Vec<Vec<String>>,
+}
+
+fn get_body(content: &str, start: usize) -> String {
+    let mut depth = 1;
+
+    for (index, char) in content[start..].char_indices() {
+        match char {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+
+                if depth == 0 {
+                    return content[start..start + index].to_string();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    content[start..].to_string()
+}
+
+fn remove_comments(content: &str) -> String {
+    content
+        .lines()
+        .map(|line| line.split('%').next().unwrap_or_default())
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+fn get_variables(content: &str) -> Vec<Variable> {
+    let content = remove_comments(content);
+
+    VARIABLE
+        .captures_iter(&content)
+        .map(|captures| {
+            let start =
+                captures.get(0).expect("Failed to parse variable.").end();
+
+            Variable {
+                name: captures[1].to_string(),
+                mode: captures[2].to_string(),
+                body: get_body(&content, start),
+            }
+        })
+        .collect()
+}
+
+fn get_references(body: &str) -> Option<Vec<String>> {
+    let tokens: Vec<&str> = body
+        .split_whitespace()
+        .filter(|token| *token != "|")
+        .collect();
+
+    if tokens.is_empty() || !tokens.iter().all(|token| token.starts_with('\\'))
+    {
+        return None;
+    }
+
+    Some(
+        tokens
+            .iter()
+            .map(|token| token.trim_start_matches('\\').to_string())
+            .collect(),
+    )
+}
+
+fn get_note_name(note: &str, accidental: Option<&str>) -> String {
+    let accidental = match accidental {
+        Some("s") => "#",
+        Some("ss" | "x") => "##",
+        Some("f") => "b",
+        Some("ff") => "bb",
+        _ => "",
+    };
+
+    format!("{}{accidental}", note.to_uppercase())
+}
+
+fn get_quality(modifiers: &str) -> String {
+    let mut steps = modifiers.split('.');
+    let first = steps.next().unwrap_or_default();
+
+    let quality = match first {
+        "aug" => "+".to_string(),
+        "maj" => String::new(),
+        first => first.to_string(),
+    };
+
+    steps.fold(quality, |quality, step| {
+        let alteration = if step.ends_with('-') {
+            "b"
+        } else if step.ends_with('+') {
+            "#"
+        } else if quality == "5" {
+            return format!("add{step}");
+        } else {
+            ""
+        };
+
+        format!("{quality}{alteration}{}", step.trim_end_matches(['-', '+']))
+    })
+}
+
+/// Converts a chordmode chord such as `fs2.:m7.5-/e` to `F#m7b5/E`. Spacer
+/// rests are skipped and rests become "N.C.".
+fn get_chord_name(token: &str) -> Option<String> {
+    if REST.is_match(token) {
+        return Some("N.C.".to_string());
+    }
+
+    let captures = CHORD.captures(token)?;
+    let root =
+        get_note_name(&captures[1], captures.get(2).map(|m| m.as_str()));
+    let quality = captures
+        .get(3)
+        .map_or_else(String::new, |modifiers| get_quality(modifiers.as_str()));
+    let bass = captures.get(4).map_or_else(String::new, |bass| {
+        format!(
+            "/{}",
+            get_note_name(bass.as_str(), captures.get(5).map(|m| m.as_str()))
+        )
+    });
+
+    Some(format!("{root}{quality}{bass}"))
+}
+
+fn get_chord_lines(body: &str) -> Vec<Vec<String>> {
+    body.lines()
+        .map(|line| {
+            line.split_whitespace()
+                .filter(|token| !token.starts_with('\\'))
+                .filter_map(get_chord_name)
+                .collect::<Vec<String>>()
+        })
+        .filter(|chords| !chords.is_empty())
+        .collect()
+}
+
+fn get_lyric_line(line: &str) -> Vec<String> {
+    let mut words: Vec<String> = vec![];
+    let mut is_continued = false;
+
+    for token in TOKEN.find_iter(line).map(|token| token.as_str()) {
+        match token {
+            "--" => {
+                if let Some(word) = words.last_mut() {
+                    word.push('-');
+                    is_continued = true;
+                }
+
+                continue;
+            }
+            "__" | "_" | "{" | "}" | "|" => continue,
+            token if token.starts_with('\\') => continue,
+            _ => (),
+        }
+
+        let word = if token.starts_with('"') {
+            token.trim_matches('"').replace("\\\"", "\"")
+        } else {
+            DURATION.replace(token, "").to_string()
+        };
+
+        if word.is_empty() {
+            continue;
+        }
+
+        if is_continued {
+            if let Some(last) = words.last_mut() {
+                last.push_str(&word);
+            }
+
+            is_continued = false;
+        } else {
+            words.push(word);
+        }
+    }
+
+    words
+}
+
+fn get_lyric_lines(body: &str) -> Vec<Vec<String>> {
+    body.lines()
+        .map(get_lyric_line)
+        .filter(|words| !words.is_empty())
+        .collect()
+}
+
+fn get_section_name(name: &str) -> String {
+    SECTION_PREFIXES
+        .iter()
+        .find_map(|prefix| name.strip_prefix(prefix))
+        .unwrap_or(name)
+        .to_string()
+}
+
+fn get_section_label(name: &str) -> String {
+    titlecase(&name.replace(['_', '-'], " "))
+}
+
+/// Sections in the order of the variable that only references other chord
+/// variables (e.g. `changes = \chords { \verse \chorus }`), or in the order
+/// they are defined when there is none.
+fn get_sections(variables: &[Variable]) -> (Vec<Section>, Vec<String>) {
+    let mut sections: Vec<Section> = vec![];
+    let mut form: Option<Vec<String>> = None;
+
+    for variable in variables
+        .iter()
+        .filter(|variable| variable.mode != "lyricmode")
+    {
+        if let Some(references) = get_references(&variable.body) {
+            form = Some(
+                references
+                    .iter()
+                    .map(|name| get_section_name(name))
+                    .collect(),
+            );
+            continue;
+        }
+
+        let name = get_section_name(&variable.name);
+        let lyrics = variables
+            .iter()
+            .filter(|lyrics| {
+                lyrics.mode == "lyricmode"
+                    && get_section_name(&lyrics.name) == name
+            })
+            .find_map(|lyrics| {
+                get_references(&lyrics.body)
+                    .is_none()
+                    .then(|| get_lyric_lines(&lyrics.body))
+            })
+            .unwrap_or_default();
+
+        sections.push(Section {
+            name,
+            chords: get_chord_lines(&variable.body),
+            lyrics,
+        });
+    }
+
+    let form = form.unwrap_or_else(|| {
+        sections
+            .iter()
+            .map(|section| section.name.to_string())
+            .collect()
+    });
+
+    (sections, form)
+}
+
+/// Chords are spread evenly over the words of the lyric line they are paired
+/// with, since the score doesn't say which syllable each chord falls on.
+fn get_chord_positions(chords: &[String], words: &[String]) -> Vec<usize> {
+    (0..chords.len())
+        .map(|index| index * words.len() / chords.len())
+        .collect()
+}
+
+fn get_chordpro_line(chords: &[String], words: &[String]) -> String {
+    if words.is_empty() {
+        return chords
+            .iter()
+            .map(|chord| format!("[{chord}]"))
+            .collect::<Vec<String>>()
+            .join(" ");
+    }
+
+    let positions = get_chord_positions(chords, words);
+
+    words
+        .iter()
+        .enumerate()
+        .map(|(index, word)| {
+            let chords: String = chords
+                .iter()
+                .zip(&positions)
+                .filter(|(_, position)| **position == index)
+                .map(|(chord, _)| format!("[{chord}]"))
+                .collect();
+
+            format!("{chords}{word}")
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn get_text_lines(chords: &[String], words: &[String]) -> Vec<String> {
+    if words.is_empty() {
+        return vec![chords.join("   ")];
+    }
+
+    let positions = get_chord_positions(chords, words);
+    let mut chord_line = String::new();
+    let mut lyric_line = String::new();
+
+    for (index, word) in words.iter().enumerate() {
+        if index > 0 {
+            lyric_line.push(' ');
+        }
+
+        let column = lyric_line.chars().count();
+
+        for (chord, _) in chords
+            .iter()
+            .zip(&positions)
+            .filter(|(_, position)| **position == index)
+        {
+            let length = chord_line.chars().count();
+            let padding = if length == 0 {
+                column
+            } else {
+                column.max(length + 1) - length
+            };
+
+            chord_line.push_str(&" ".repeat(padding));
+            chord_line.push_str(chord);
+        }
+
+        lyric_line.push_str(word);
+    }
+
+    if chords.is_empty() {
+        vec![lyric_line]
+    } else {
+        vec![chord_line, lyric_line]
+    }
+}
+
+fn get_section_lines(section: &Section, format: ChartFormat) -> Vec<String> {
+    let length = section.chords.len().max(section.lyrics.len());
+    let mut lines = vec![];
+
+    for index in 0..length {
+        let chords = section.chords.get(index).cloned().unwrap_or_default();
+        let words = section.lyrics.get(index).cloned().unwrap_or_default();
+
+        match format {
+            ChartFormat::ChordPro => {
+                lines.push(get_chordpro_line(&chords, &words))
+            }
+            ChartFormat::Text => {
+                lines.append(&mut get_text_lines(&chords, &words))
+            }
+        }
+    }
+
+    lines
+}
+
+fn get_section_chart(
+    section: &Section,
+    format: ChartFormat,
+    is_repeat: bool,
+) -> Vec<String> {
+    let label = get_section_label(&section.name);
+    let kind = section.name.split(['_', '-']).next().unwrap_or_default();
+
+    match format {
+        ChartFormat::ChordPro => {
+            if is_repeat {
+                return if kind == "chorus" {
+                    vec!["{chorus}".to_string()]
+                } else {
+                    vec![format!("{{comment: {label}}}")]
+                };
+            }
+
+            let mut lines = if matches!(kind, "verse" | "chorus" | "bridge") {
+                vec![format!("{{start_of_{kind}: {label}}}")]
+            } else {
+                vec![format!("{{comment: {label}}}")]
+            };
+
+            lines.append(&mut get_section_lines(section, format));
+
+            if matches!(kind, "verse" | "chorus" | "bridge") {
+                lines.push(format!("{{end_of_{kind}}}"));
+            }
+
+            lines
+        }
+        ChartFormat::Text => {
+            let mut lines = vec![format!("[{label}]")];
+
+            if !is_repeat {
+                lines.append(&mut get_section_lines(section, format));
+            }
+
+            lines
+        }
+    }
+}
+
+fn get_header(ly_file: &str, format: ChartFormat) -> Vec<String> {
+    let metadata = get_composition_metadata(&ly_file.to_string());
+    let fields = [
+        ("title", metadata.title),
+        ("subtitle", metadata.subtitle),
+        ("artist", metadata.composer),
+    ];
+
+    fields
+        .into_iter()
+        .filter_map(|(field, value)| {
+            value.map(|value| match format {
+                ChartFormat::ChordPro => format!("{{{field}: {value}}}"),
+                ChartFormat::Text => value,
+            })
+        })
+        .collect()
+}
+
+pub fn get_chart(
+    ly_file: &str,
+    format: ChartFormat,
+) -> Result<String, String> {
+    let directory = Path::new(ly_file)
+        .parent()
+        .and_then(|directory| directory.to_str())
+        .expect("Failed to get score directory.");
+
+    let mut variables = vec![];
+
+    for file in glob(&format!("{directory}/*.*ly"))
+        .expect("Failed to read glob pattern")
+        .flatten()
+    {
+        if let Ok(content) = read_to_string(&file) {
+            variables.append(&mut get_variables(&content));
+        }
+    }
+
+    let (sections, form) = get_sections(&variables);
+
+    if sections.is_empty() {
+        return Err(format!("No \\chordmode sections found in {directory}"));
+    }
+
+    let mut lines = get_header(ly_file, format);
+    let mut exported: Vec<&str> = vec![];
+
+    for name in &form {
+        let Some(section) =
+            sections.iter().find(|section| &section.name == name)
+        else {
+            continue;
+        };
+
+        let is_repeat = exported.contains(&name.as_str());
+
+        lines.push(String::new());
+        lines.append(&mut get_section_chart(section, format, is_repeat));
+        exported.push(name);
+    }
+
+    Ok(format!("{}\n", lines.join("\n")))
+}
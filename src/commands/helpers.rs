@@ -1,11 +1,14 @@
 use std::fmt::{Display, Formatter, Result};
+use std::fs::{create_dir_all, write};
+use std::io;
+use std::path::Path;
 
 use bat::{PagingMode, PrettyPrinter};
 use clap::ValueEnum;
 use convert_case::{Case::Kebab, Casing};
 use serde::{Deserialize, Serialize};
 
-use super::{table, HelperCommand};
+use super::{table, HelperCommand, OutputFormat};
 
 const HELPER_FILES: &[(&str, &[u8])] =
     &include!(concat!(env!("OUT_DIR"), "/helper_files.rs"));
@@ -53,7 +56,7 @@ fn display_helper_file(helper: &Helper) {
     }
 }
 
-pub fn main(command: &Option<HelperCommand>) {
+pub fn main(command: &Option<HelperCommand>, format: &Option<OutputFormat>) {
     if command.is_some() {
         match command.as_ref().expect("Failed to parse helper command.") {
             HelperCommand::Show { helper } => display_helper_file(helper),
@@ -103,7 +106,29 @@ pub fn main(command: &Option<HelperCommand>) {
         .map(|row| row.iter().map(ToString::to_string).collect())
         .collect();
 
-    table::print(&titles, rows);
+    table::print(&titles, rows, format);
+}
+
+/// File names of the built-in helpers (e.g. "settings.ily").
+pub fn get_helper_file_names() -> Vec<String> {
+    HELPER_FILES
+        .iter()
+        .filter_map(|(file_path, _)| Path::new(file_path).file_name())
+        .map(|file_name| file_name.to_string_lossy().to_string())
+        .collect()
+}
+
+pub fn write_helper_files(directory: &str) -> io::Result<()> {
+    let helpers_directory = format!("{directory}/helpers");
+    create_dir_all(&helpers_directory)?;
+
+    for (file_path, content) in HELPER_FILES {
+        if let Some(file_name) = Path::new(file_path).file_name() {
+            write(Path::new(&helpers_directory).join(file_name), content)?;
+        }
+    }
+
+    Ok(())
 }
 
 pub fn pushln(lines: &mut String, text: &str) {
@@ -0,0 +1,260 @@
+pub mod chordpro;
+
+use std::fs::{create_dir_all, read_to_string};
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::Command;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use titlecase::titlecase;
+
+use super::compile::get_binary_command;
+use super::create::{create_file, get_file_system_name};
+use super::lilypond::resolve_lilypond_version;
+use super::scores::TEMPORARY_DIRECTORY;
+use super::templates::TemplateFile;
+use super::{get_composer_from_arg, get_scores_directory_from_arg};
+
+static TITLE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?m)^\s*title\s*=\s*"([^"]*)""#)
+        .expect("Failed to create title regex.")
+});
+
+static COMPOSER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?m)^\s*composer\s*=\s*"([^"]*)""#)
+        .expect("Failed to create composer regex.")
+});
+
+static VERSION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?m)^\s*\\version\s+"[^"]*"\s*$"#)
+        .expect("Failed to create version regex.")
+});
+
+static LANGUAGE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^\s*\\language\s")
+        .expect("Failed to create language regex.")
+});
+
+static SETTINGS_INCLUDE: &str = "\\include \"helpers/settings.ily\"";
+
+/// The note names abc2ly and midi2ly write, which `helpers/settings.ily`
+/// would otherwise switch to english.
+static CONVERTER_LANGUAGE: &str = "\\language \"nederlands\"";
+
+/// Arguments for converters that can write english note names directly.
+fn get_converter_arguments(converter: &str) -> &'static [&'static str] {
+    if converter == "musicxml2ly" {
+        &["--language=english"]
+    } else {
+        &[]
+    }
+}
+
+fn get_converter(file: &Path) -> Option<&'static str> {
+    let extension = file.extension()?.to_str()?.to_lowercase();
+
+    match extension.as_str() {
+        "musicxml" | "xml" | "mxl" => Some("musicxml2ly"),
+        "abc" => Some("abc2ly"),
+        "mid" | "midi" => Some("midi2ly"),
+        _ => None,
+    }
+}
+
+fn get_header_value(regex: &Regex, content: &str) -> Option<String> {
+    regex
+        .captures(content)
+        .map(|captures| captures[1].trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn get_file_stem(file: &Path) -> String {
+    file.file_stem()
+        .and_then(|stem| stem.to_str())
+        .expect("Failed to parse input file name.")
+        .to_string()
+}
+
+fn convert(
+    converter: &str,
+    file: &Path,
+    lilypond_version: &str,
+) -> Result<String, String> {
+    let output_directory = format!("{TEMPORARY_DIRECTORY}/import");
+    create_dir_all(&output_directory)
+        .expect("Failed to create import directory.");
+
+    let output_file = format!("{output_directory}/{}.ly", get_file_stem(file));
+    let command = get_binary_command(converter, lilypond_version.to_string());
+
+    let output = Command::new(&command)
+        .args(["--output", &output_file])
+        .args(get_converter_arguments(converter))
+        .arg(file)
+        .output()
+        .map_err(|err| format!("Failed to run {converter} ({err})"))?;
+
+    if !output.status.success() {
+        io::stdout()
+            .write_all(output.stderr.as_ref())
+            .expect("Failed to print converter output.");
+
+        return Err(format!("Failed to convert {}", file.display()));
+    }
+
+    read_to_string(&output_file)
+        .map_err(|err| format!("Failed to read {output_file} ({err})"))
+}
+
+/// Replaces any `\version` statement from the converter with
+/// `lilypond_version` and includes the settings helper after it, restoring
+/// the converter's dutch note names unless it set a language itself.
+pub fn stamp_score(content: &str, lilypond_version: &str) -> String {
+    let version = format!("\\version \"{lilypond_version}\"");
+    let content = VERSION.replace_all(content, "").trim().to_string();
+
+    let settings = if content.contains(SETTINGS_INCLUDE) {
+        String::new()
+    } else if LANGUAGE.is_match(&content) {
+        format!("{SETTINGS_INCLUDE}\n\n")
+    } else {
+        format!("{SETTINGS_INCLUDE}\n{CONVERTER_LANGUAGE}\n\n")
+    };
+
+    format!("{version}\n\n{settings}{content}\n")
+}
+
+pub fn write_score(
+    title: &str,
+    composer: &str,
+    templates: Vec<TemplateFile>,
+    scores_directory: &Option<String>,
+    force: bool,
+) -> Result<Vec<String>, String> {
+    let scores_directory = get_scores_directory_from_arg(scores_directory);
+    let composer_directory = get_file_system_name(composer);
+    let file_system_title = get_file_system_name(title);
+    let parent = format!(
+        "{scores_directory}/scores/{composer_directory}/{file_system_title}"
+    );
+
+    if !force && Path::new(&parent).exists() {
+        return Err(format!(
+            "\"{parent}\" already exists. Use --force to overwrite it."
+        ));
+    }
+
+    create_dir_all(&parent).expect("Failed to create score parent directory.");
+
+    Ok(templates
+        .into_iter()
+        .map(|template| {
+            create_file(template, &parent, file_system_title.clone(), force)
+        })
+        .collect())
+}
+
+fn import_file(
+    file: &Path,
+    title: &Option<String>,
+    composer: &Option<String>,
+    lilypond_version: &Option<String>,
+    scores_directory: &Option<String>,
+    force: bool,
+) -> Result<Vec<String>, String> {
+    let converter = get_converter(file).ok_or_else(|| {
+        format!(
+            "Unrecognized file type: \"{}\". Expected MusicXML, ABC or MIDI.",
+            file.display()
+        )
+    })?;
+
+    let version = resolve_lilypond_version(lilypond_version)
+        .ok_or_else(|| "Failed to find a lilypond version.".to_string())?;
+
+    let content = convert(converter, file, &version)?;
+
+    let title = title
+        .clone()
+        .or_else(|| get_header_value(&TITLE, &content))
+        .unwrap_or_else(|| {
+            titlecase(&get_file_stem(file).replace(['-', '_'], " "))
+        });
+
+    let composer = composer
+        .clone()
+        .or_else(|| get_header_value(&COMPOSER, &content))
+        .unwrap_or_else(|| get_composer_from_arg(&None));
+
+    let template = TemplateFile {
+        filename: None,
+        content: stamp_score(&content, &version),
+    };
+
+    write_score(&title, &composer, vec![template], scores_directory, force)
+}
+
+pub fn main(
+    file: &String,
+    title: &Option<String>,
+    composer: &Option<String>,
+    is_chordpro: bool,
+    force: bool,
+    lilypond_version: &Option<String>,
+    scores_directory: &Option<String>,
+) {
+    let file = Path::new(file);
+
+    if !file.exists() {
+        println!("File not found: \"{}\"", file.display());
+        return;
+    }
+
+    let import = if is_chordpro {
+        chordpro::import_file
+    } else {
+        import_file
+    };
+
+    match import(
+        file,
+        title,
+        composer,
+        lilypond_version,
+        scores_directory,
+        force,
+    ) {
+        Ok(files) => {
+            for file in files {
+                println!("Imported {file}");
+            }
+        }
+        Err(message) => println!("{message}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::stamp_score;
+
+    #[test]
+    fn keeps_dutch_accidentals_after_settings() {
+        let content = "\\version \"2.18.2\"\n{ cis'4 bes as es }";
+
+        assert_eq!(
+            stamp_score(content, "2.24.3"),
+            "\\version \"2.24.3\"\n\n\\include \"helpers/settings.ily\"\n\\language \"nederlands\"\n\n{ cis'4 bes as es }\n"
+        );
+    }
+
+    #[test]
+    fn keeps_converter_language() {
+        let content = "\\language \"english\"\n{ cs'4 bf af ef }";
+
+        assert_eq!(
+            stamp_score(content, "2.24.3"),
+            "\\version \"2.24.3\"\n\n\\include \"helpers/settings.ily\"\n\n\\language \"english\"\n{ cs'4 bf af ef }\n"
+        );
+    }
+}
@@ -0,0 +1,619 @@
+use std::fs::read_to_string;
+use std::path::Path;
+
+use indoc::formatdoc;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+use super::write_score;
+use crate::commands::create::{get_template_settings, ScoreFileSettings};
+use crate::commands::get_composer_from_arg;
+use crate::commands::templates::ensemble::indent;
+use crate::commands::templates::lead::get_lead_templates;
+use crate::commands::templates::settings::TemplateSettings;
+use crate::commands::templates::{Template, TemplateFile};
+
+static DIRECTIVE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\{\s*([\w-]+)\s*(?::\s*(.*?))?\s*\}$")
+        .expect("Failed to create directive regex.")
+});
+
+static CHORD: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\[([^\]]*)\]").expect("Failed to create chord regex.")
+});
+
+static CHORD_NAME: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^([A-Ga-g])([#b]?)([^/]*)(?:/([A-Ga-g])([#b]?))?$")
+        .expect("Failed to create chord name regex.")
+});
+
+static ALTERATION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"([#b])(\d+)").expect("Failed to create alteration regex.")
+});
+
+static ORDINALS: [&str; 9] = [
+    "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+];
+
+#[derive(Debug, Default)]
+struct Line {
+    /// Each chord with the index of the word it starts on.
+    chords: Vec<(usize, String)>,
+    words: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct Section {
+    variable: String,
+    lines: Vec<Line>,
+}
+
+#[derive(Debug, Default)]
+struct Song {
+    title: Option<String>,
+    subtitle: Option<String>,
+    artist: Option<String>,
+    key: Option<String>,
+    time: Option<String>,
+    tempo: Option<String>,
+    sections: Vec<Section>,
+    form: Vec<usize>,
+    is_section_open: bool,
+}
+
+impl Song {
+    fn get_variable(&self, kind: &str) -> String {
+        let count = self
+            .sections
+            .iter()
+            .filter(|section| section.variable.split('_').next() == Some(kind))
+            .count();
+
+        match count {
+            0 => kind.to_string(),
+            count => ORDINALS.get(count - 1).map_or_else(
+                || format!("{kind}_{}", ORDINALS[ORDINALS.len() - 1]),
+                |ordinal| format!("{kind}_{ordinal}"),
+            ),
+        }
+    }
+
+    fn start_section(&mut self, kind: &str) {
+        let variable = self.get_variable(kind);
+
+        self.sections.push(Section {
+            variable,
+            ..Section::default()
+        });
+        self.form.push(self.sections.len() - 1);
+        self.is_section_open = true;
+    }
+
+    /// `{chorus}` repeats the most recent chorus.
+    fn repeat_section(&mut self, kind: &str) {
+        if let Some(index) = self.sections.iter().rposition(|section| {
+            section.variable.split('_').next() == Some(kind)
+        }) {
+            self.form.push(index);
+        }
+    }
+
+    /// Lines outside of a section start a new verse.
+    fn get_current_section(&mut self) -> &mut Section {
+        if !self.is_section_open {
+            self.start_section("verse");
+        }
+
+        self.sections
+            .last_mut()
+            .expect("Failed to get song section.")
+    }
+}
+
+fn get_section_kind(directive: &str) -> Option<&str> {
+    match directive {
+        "start_of_verse" | "sov" => Some("verse"),
+        "start_of_chorus" | "soc" => Some("chorus"),
+        "start_of_bridge" | "sob" => Some("bridge"),
+        _ => directive.strip_prefix("start_of_"),
+    }
+}
+
+fn get_chord_quality(quality: &str) -> String {
+    let quality = match quality {
+        "" | "maj" | "M" => "",
+        "m" | "min" | "-" => "m",
+        "M7" | "Δ" | "Δ7" | "ma7" => "maj7",
+        "-7" | "min7" => "m7",
+        "ø" | "ø7" | "m7b5" | "-7b5" => "m7.5-",
+        "o" | "°" => "dim",
+        "o7" | "°7" => "dim7",
+        "+" => "aug",
+        "sus" => "sus4",
+        "add9" | "add2" => "5.9",
+        quality => quality,
+    };
+
+    let quality = ALTERATION.replace_all(quality, |captures: &Captures| {
+        let alteration = if &captures[1] == "#" { "+" } else { "-" };
+        format!(".{}{alteration}", &captures[2])
+    });
+
+    if quality.is_empty() {
+        String::new()
+    } else {
+        format!(":{}", quality.trim_start_matches('.'))
+    }
+}
+
+fn get_note_name(note: &str, accidental: &str) -> String {
+    let accidental = match accidental {
+        "#" => "s",
+        "b" => "f",
+        _ => "",
+    };
+
+    format!("{}{accidental}", note.to_lowercase())
+}
+
+/// Converts a ChordPro chord such as `F#m7b5/E` to chordmode
+/// (`fs1:m7.5-/e`) lasting `duration`.
+fn get_chord(chord: &str, duration: &str) -> String {
+    CHORD_NAME.captures(chord.trim()).map_or_else(
+        || format!("r{duration}"),
+        |captures| {
+            let root = get_note_name(&captures[1], &captures[2]);
+            let quality = get_chord_quality(&captures[3]);
+            let bass = captures.get(4).map_or_else(String::new, |bass| {
+                let accidental = captures
+                    .get(5)
+                    .map_or("", |accidental| accidental.as_str());
+                format!("/{}", get_note_name(bass.as_str(), accidental))
+            });
+
+            format!("{root}{duration}{quality}{bass}")
+        },
+    )
+}
+
+fn get_lyric(word: &str) -> String {
+    if word
+        .chars()
+        .any(|char| char.is_ascii_digit() || "{}\\\"#$%".contains(char))
+    {
+        format!("\"{}\"", word.replace('\\', "").replace('"', "\\\""))
+    } else {
+        word.to_string()
+    }
+}
+
+/// The index of the word a chord at `offset` of the chordless `text` starts
+/// on: the word it's in (e.g. "A[G]mazing"), or else the next one.
+fn get_word_index(text: &str, offset: usize) -> usize {
+    let is_in_word = text[offset..]
+        .chars()
+        .next()
+        .is_some_and(|char| !char.is_whitespace());
+    let words = text[..offset].split_whitespace().count();
+    let is_word_split = is_in_word
+        && text[..offset]
+            .chars()
+            .last()
+            .is_some_and(|char| !char.is_whitespace());
+
+    if is_word_split {
+        words - 1
+    } else {
+        words
+    }
+}
+
+fn parse_line(song: &mut Song, line: &str) {
+    let mut text = String::new();
+    let mut chords = vec![];
+    let mut position = 0;
+
+    for captures in CHORD.captures_iter(line) {
+        let chord = captures.get(0).expect("Failed to get chord match.");
+
+        text.push_str(&line[position..chord.start()]);
+        chords.push((text.len(), captures[1].to_string()));
+        position = chord.end();
+    }
+
+    text.push_str(&line[position..]);
+
+    let chords: Vec<(usize, String)> = chords
+        .into_iter()
+        .map(|(offset, chord)| (get_word_index(&text, offset), chord))
+        .collect();
+    let words: Vec<String> = text.split_whitespace().map(get_lyric).collect();
+
+    if chords.is_empty() && words.is_empty() {
+        return;
+    }
+
+    song.get_current_section()
+        .lines
+        .push(Line { chords, words });
+}
+
+fn parse_song(content: &str) -> Song {
+    let mut song = Song::default();
+    let mut is_skipping = false;
+
+    for line in content.lines() {
+        let line = line.trim();
+
+        if line.starts_with('#') {
+            continue;
+        }
+
+        let Some(captures) = DIRECTIVE.captures(line) else {
+            if !is_skipping {
+                parse_line(&mut song, line);
+            }
+
+            continue;
+        };
+
+        let directive = captures[1].to_lowercase();
+        let value = captures.get(2).map(|value| value.as_str().to_string());
+
+        match directive.as_str() {
+            "start_of_tab" | "sot" | "start_of_grid" | "sog" => {
+                is_skipping = true;
+            }
+            "end_of_tab" | "eot" | "end_of_grid" | "eog" => {
+                is_skipping = false;
+            }
+            "title" | "t" => song.title = value,
+            "subtitle" | "st" => song.subtitle = value,
+            "artist" | "composer" => {
+                song.artist = song.artist.take().or(value);
+            }
+            "key" => song.key = value,
+            "time" => song.time = value,
+            "tempo" => song.tempo = value,
+            "chorus" => song.repeat_section("chorus"),
+            directive if directive.starts_with("end_of_") => {
+                song.is_section_open = false;
+            }
+            "eov" | "eoc" | "eob" => song.is_section_open = false,
+            directive => {
+                if let Some(kind) = get_section_kind(directive) {
+                    song.start_section(kind);
+                }
+            }
+        }
+    }
+
+    song
+}
+
+fn get_section_variables(song: &Song, prefix: &str) -> String {
+    song.form
+        .iter()
+        .map(|index| format!("\\{prefix}_{}", song.sections[*index].variable))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Every section as `{prefix}_{section} = {mode}{ ... }`.
+fn get_sections(
+    song: &Song,
+    prefix: &str,
+    mode: &str,
+    get_lines: impl Fn(&Section) -> Vec<String>,
+) -> String {
+    song.sections
+        .iter()
+        .map(|section| {
+            let variable = &section.variable;
+            let lines = indent(&get_lines(section).join("\n"), 2);
+
+            format!("{prefix}_{variable} = {mode}{{\n{lines}\n}}\n")
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// The sections followed by `{prefix} = {mode}{ ... }` playing them in the
+/// song's order.
+fn get_part(
+    song: &Song,
+    prefix: &str,
+    mode: &str,
+    get_lines: impl Fn(&Section) -> Vec<String>,
+) -> String {
+    let sections = get_sections(song, prefix, mode, get_lines);
+    let variables = indent(&get_section_variables(song, prefix), 2);
+
+    formatdoc!(
+        "
+        {sections}
+        {prefix} = {mode}{{
+        {variables}
+        }}
+"
+    )
+}
+
+/// Beats per bar and the note value of a beat, e.g. 6 and 8 in 6/8.
+struct Meter {
+    beats: usize,
+    beat: usize,
+}
+
+impl Meter {
+    fn from_settings(settings: &TemplateSettings) -> Self {
+        let time = settings.time.trim_start_matches("\\time ");
+        let (numerator, denominator) =
+            time.split_once('/').unwrap_or(("4", "4"));
+        let beats = numerator
+            .split('+')
+            .filter_map(|beats| beats.trim().parse::<usize>().ok())
+            .sum();
+
+        Self {
+            beats,
+            beat: denominator.trim().parse().unwrap_or(4),
+        }
+    }
+
+    /// The duration of `count` beats, e.g. `2.` for three quarter notes.
+    fn get_duration(&self, count: usize) -> String {
+        let beat = self.beat;
+        let dotted = count / 3 * 2;
+
+        if count.is_power_of_two() && beat.is_multiple_of(count) {
+            (beat / count).to_string()
+        } else if count.is_multiple_of(3)
+            && dotted.is_power_of_two()
+            && beat.is_multiple_of(dotted)
+        {
+            format!("{}.", beat / dotted)
+        } else {
+            format!("{beat}*{count}")
+        }
+    }
+
+    /// ChordPro has no rhythm, so each word of `line` gets a beat and the
+    /// line is padded to whole bars. A line of chords alone gets a bar per
+    /// chord.
+    fn get_line_beats(&self, line: &Line) -> usize {
+        if line.words.is_empty() {
+            return line.chords.len() * self.beats;
+        }
+
+        let beats = line
+            .chords
+            .last()
+            .map_or(0, |(start, _)| start + 1)
+            .max(line.words.len());
+
+        beats.div_ceil(self.beats) * self.beats
+    }
+
+    /// The beat each chord of `line` starts on.
+    fn get_chord_starts(&self, line: &Line) -> Vec<usize> {
+        line.chords
+            .iter()
+            .enumerate()
+            .map(|(index, (start, _))| {
+                if line.words.is_empty() {
+                    index * self.beats
+                } else {
+                    *start
+                }
+            })
+            .collect()
+    }
+}
+
+/// Chords lasting until the next chord, with a skip before the first.
+fn get_changes_line(line: &Line, meter: &Meter) -> String {
+    let beats = meter.get_line_beats(line);
+    let starts = meter.get_chord_starts(line);
+    let mut changes = vec![];
+
+    match starts.first() {
+        Some(0) => {}
+        first => {
+            let skip = first.copied().unwrap_or(beats);
+            changes.push(format!("s{}", meter.get_duration(skip)));
+        }
+    }
+
+    for (index, (start, (_, chord))) in
+        starts.iter().zip(&line.chords).enumerate()
+    {
+        let end = starts.get(index + 1).copied().unwrap_or(beats);
+
+        // A later chord on the same word replaces this one.
+        if end > *start {
+            changes.push(get_chord(chord, &meter.get_duration(end - start)));
+        }
+    }
+
+    format!("| {}", changes.join(" "))
+}
+
+/// A placeholder note per word, then rests to the end of the line.
+fn get_melody_line(line: &Line, meter: &Meter) -> String {
+    let beats = meter.get_line_beats(line);
+    let mut notes: Vec<String> = line
+        .words
+        .iter()
+        .enumerate()
+        .map(|(index, _)| {
+            if index == 0 {
+                format!("c{}", meter.beat)
+            } else {
+                "c".to_string()
+            }
+        })
+        .collect();
+
+    if beats > line.words.len() {
+        notes.push(format!(
+            "r{}",
+            meter.get_duration(beats - line.words.len())
+        ));
+    }
+
+    format!("| {}", notes.join(" "))
+}
+
+fn get_changes(song: &Song, meter: &Meter) -> String {
+    get_part(song, "changes", "\\chordmode ", |section| {
+        section
+            .lines
+            .iter()
+            .map(|line| get_changes_line(line, meter))
+            .collect()
+    })
+}
+
+fn get_lyrics(song: &Song) -> String {
+    get_part(song, "words", "\\lyricmode ", |section| {
+        section
+            .lines
+            .iter()
+            .filter(|line| !line.words.is_empty())
+            .map(|line| line.words.join(" "))
+            .collect()
+    })
+}
+
+fn get_melody(song: &Song, meter: &Meter) -> String {
+    let sections =
+        get_sections(song, "melody", "\\relative c'' ", |section| {
+            section
+                .lines
+                .iter()
+                .map(|line| get_melody_line(line, meter))
+                .collect()
+        });
+    let variables = indent(&get_section_variables(song, "melody"), 2);
+
+    formatdoc!(
+        "
+        {sections}
+        melody = {{
+        {variables}
+        }}
+"
+    )
+}
+
+/// The duration of a full bar, e.g. `2.` in 3/4 or `1*5/8` in 5/8.
+fn get_bar_duration(meter: &Meter) -> String {
+    match (meter.beats, meter.beat) {
+        (4, 4) | (2, 2) => "1".to_string(),
+        (3, 4) | (6, 8) => "2.".to_string(),
+        (2, 4) | (1, 2) => "2".to_string(),
+        (3, 8) => "4.".to_string(),
+        (12, 8) => "1.".to_string(),
+        (beats, beat) => format!("1*{beats}/{beat}"),
+    }
+}
+
+fn get_structure(
+    song: &Song,
+    settings: &TemplateSettings,
+    meter: &Meter,
+) -> String {
+    let key_and_time = settings.get_key_and_time(2);
+    let duration = get_bar_duration(meter);
+    let sections = get_sections(song, "structure", "", |section| {
+        let beats: usize = section
+            .lines
+            .iter()
+            .map(|line| meter.get_line_beats(line))
+            .sum();
+
+        match beats / meter.beats {
+            0 => vec![],
+            bars => vec![format!("| s{duration} * {bars}")],
+        }
+    });
+    let variables = indent(&get_section_variables(song, "structure"), 2);
+
+    formatdoc!(
+        "
+        key_and_time = {{
+          {key_and_time}
+        }}
+
+        {sections}
+        structure = {{
+          \\key_and_time
+        {variables}
+        }}
+"
+    )
+}
+
+fn get_title_from_file(file: &Path) -> String {
+    file.file_stem()
+        .and_then(|stem| stem.to_str())
+        .expect("Failed to parse input file name.")
+        .replace(['-', '_'], " ")
+}
+
+pub fn import_file(
+    file: &Path,
+    title: &Option<String>,
+    composer: &Option<String>,
+    lilypond_version: &Option<String>,
+    scores_directory: &Option<String>,
+    force: bool,
+) -> Result<Vec<String>, String> {
+    let content = read_to_string(file)
+        .map_err(|err| format!("Failed to read {} ({err})", file.display()))?;
+    let song = parse_song(&content);
+
+    let title = title
+        .clone()
+        .or_else(|| song.title.clone())
+        .unwrap_or_else(|| get_title_from_file(file));
+    let composer = composer
+        .clone()
+        .or_else(|| song.artist.clone())
+        .unwrap_or_else(|| get_composer_from_arg(&None));
+
+    let settings = ScoreFileSettings {
+        title: title.to_string(),
+        subtitle: song.subtitle.clone(),
+        composer: Some(composer.to_string()),
+        template: Some(Template::Lead),
+        key: song.key.clone(),
+        time: song.time.clone(),
+        tempo: song.tempo.clone(),
+        ..ScoreFileSettings::default()
+    };
+
+    let settings = get_template_settings(&settings, lilypond_version)?;
+    let meter = Meter::from_settings(&settings);
+
+    let templates: Vec<TemplateFile> = get_lead_templates(&settings)
+        .into_iter()
+        .map(|template| {
+            let content = match template.filename.as_deref() {
+                Some("changes") => get_changes(&song, &meter),
+                Some("lyrics") => get_lyrics(&song),
+                Some("melody") => get_melody(&song, &meter),
+                Some("structure") => get_structure(&song, &settings, &meter),
+                _ => template.content,
+            };
+
+            TemplateFile {
+                filename: template.filename,
+                content,
+            }
+        })
+        .collect();
+
+    write_score(&title, &composer, templates, scores_directory, force)
+}
@@ -0,0 +1,73 @@
+use std::collections::BTreeSet;
+use std::fs::{canonicalize, read_to_string};
+use std::path::{Path, PathBuf};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static INCLUDE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?m)^[^%\n]*\\include\s+"([^"]+)""#)
+        .expect("Failed to create include regex.")
+});
+
+/// Resolves `include` the way lilypond does: relative to the including file,
+/// then to the include path (the scores directory).
+pub fn resolve_include(
+    include: &str,
+    directory: &Path,
+    scores_directory: &Path,
+) -> Option<PathBuf> {
+    [directory.join(include), scores_directory.join(include)]
+        .iter()
+        .find_map(|path| canonicalize(path).ok())
+}
+
+/// `file` and every file it includes, directly or indirectly.
+pub fn get_dependencies(
+    file: &Path,
+    scores_directory: &str,
+) -> BTreeSet<PathBuf> {
+    let scores_directory = Path::new(scores_directory);
+    let mut dependencies = BTreeSet::new();
+    let mut files: Vec<PathBuf> = canonicalize(file).into_iter().collect();
+
+    while let Some(file) = files.pop() {
+        if !dependencies.insert(file.clone()) {
+            continue;
+        }
+
+        let Ok(content) = read_to_string(&file) else {
+            continue;
+        };
+
+        let directory = file.parent().unwrap_or(scores_directory);
+
+        files.extend(INCLUDE.captures_iter(&content).filter_map(|captures| {
+            resolve_include(&captures[1], directory, scores_directory)
+        }));
+    }
+
+    dependencies
+}
+
+/// The `scores` that depend on any of `changed_files`.
+pub fn get_dependent_scores(
+    changed_files: &[PathBuf],
+    scores: &[PathBuf],
+    scores_directory: &str,
+) -> Vec<PathBuf> {
+    let changed_files: Vec<PathBuf> = changed_files
+        .iter()
+        .filter_map(|file| canonicalize(file).ok())
+        .collect();
+
+    scores
+        .iter()
+        .filter(|score| {
+            let dependencies = get_dependencies(score, scores_directory);
+
+            changed_files.iter().any(|file| dependencies.contains(file))
+        })
+        .cloned()
+        .collect()
+}
@@ -1,23 +1,27 @@
 use std::{
-    fs::File,
+    fs::{create_dir_all, remove_dir_all, File},
     io::{BufRead, BufReader},
+    path::Path,
 };
 
 use bat::{PagingMode, PrettyPrinter};
 use convert_case::{Case::Title, Casing};
 
 use super::helpers::pushln;
-use super::scores::{get_score_ly_file, get_selected_items, search};
-
-struct CompositionMetadata {
-    lilypond_version: Option<String>,
-    title: Option<String>,
-    subtitle: Option<String>,
-    composer: Option<String>,
-    arranger: Option<String>,
-    key: Option<String>,
-    time: Option<String>,
-    instruments: Vec<String>,
+use super::render::{display_image, render_png};
+use super::scores::{
+    get_score_ly_file, get_selected_items, search, TEMPORARY_DIRECTORY,
+};
+
+pub struct CompositionMetadata {
+    pub lilypond_version: Option<String>,
+    pub title: Option<String>,
+    pub subtitle: Option<String>,
+    pub composer: Option<String>,
+    pub arranger: Option<String>,
+    pub key: Option<String>,
+    pub time: Option<String>,
+    pub instruments: Vec<String>,
 }
 
 fn print_info(composition_metadata: CompositionMetadata) {
@@ -80,7 +84,7 @@ fn print_info(composition_metadata: CompositionMetadata) {
         .expect("Failed to print score info.");
 }
 
-fn display_score_info(score: &String) {
+pub fn get_composition_metadata(score: &String) -> CompositionMetadata {
     let file = File::open(score).expect("file not found");
     let buf_reader = BufReader::new(file);
     let lines: Vec<String> = buf_reader
@@ -155,7 +159,7 @@ fn display_score_info(score: &String) {
         }
     }
 
-    let metadata = CompositionMetadata {
+    CompositionMetadata {
         lilypond_version,
         title,
         subtitle,
@@ -164,9 +168,37 @@ fn display_score_info(score: &String) {
         key,
         time,
         instruments,
-    };
+    }
+}
 
-    print_info(metadata);
+fn display_first_page(score: &String, scores_directory: &Option<String>) {
+    let score = Path::new(score);
+    let stem = score
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .expect("Failed to get score file name.");
+    let directory = format!("{TEMPORARY_DIRECTORY}/render/{stem}");
+
+    let _ = remove_dir_all(&directory);
+    create_dir_all(&directory).expect("Failed to create render directory.");
+
+    if let Some(png) =
+        render_png(score, &directory, &[], &None, scores_directory)
+    {
+        display_image(&png, &None);
+    }
+}
+
+fn display_score_info(
+    score: &String,
+    preview: bool,
+    scores_directory: &Option<String>,
+) {
+    print_info(get_composition_metadata(score));
+
+    if preview {
+        display_first_page(score, scores_directory);
+    }
 }
 
 pub fn main(
@@ -174,6 +206,7 @@ pub fn main(
     search_artist: bool,
     search_title: bool,
     use_all_matches: bool,
+    preview: bool,
     scores_directory: &Option<String>,
 ) {
     let matching_scores = search(
@@ -191,7 +224,7 @@ pub fn main(
                 let score = score.output().to_string();
 
                 if let Some(ly_file) = get_score_ly_file(&score) {
-                    display_score_info(&ly_file);
+                    display_score_info(&ly_file, preview, scores_directory);
                 }
             }
         }
@@ -201,7 +234,7 @@ pub fn main(
                 score.to_str().expect("Failed to parse score.").to_string();
 
             if let Some(ly_file) = get_score_ly_file(&score) {
-                display_score_info(&ly_file);
+                display_score_info(&ly_file, preview, scores_directory);
             }
         }
     }
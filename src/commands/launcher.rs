@@ -0,0 +1,272 @@
+use std::env::var;
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::config::Config;
+
+/// Editors run in the terminal unless the `editor_is_terminal` setting says
+/// otherwise.
+static TERMINAL_EDITORS: [&str; 14] = [
+    "helix",
+    "hx",
+    "joe",
+    "kak",
+    "mg",
+    "micro",
+    "nano",
+    "ne",
+    "nvim",
+    "vi",
+    "vim",
+    "emacs -nw",
+    "emacsclient -nw",
+    "emacsclient -t",
+];
+
+/// How known editors take a position, for editor settings (such as
+/// `$EDITOR`) without `{file}`, `{line}` or `{column}` placeholders.
+static EDITOR_POSITIONS: [(&str, &str); 20] = [
+    ("code", "--goto {file}:{line}:{column}"),
+    ("codium", "--goto {file}:{line}:{column}"),
+    ("emacs", "+{line}:{column} {file}"),
+    ("emacsclient", "+{line}:{column} {file}"),
+    ("gedit", "+{line}:{column} {file}"),
+    ("gvim", "+{line} {file}"),
+    ("helix", "{file}:{line}:{column}"),
+    ("hx", "{file}:{line}:{column}"),
+    ("joe", "+{line} {file}"),
+    ("kak", "+{line}:{column} {file}"),
+    ("kate", "--line {line} --column {column} {file}"),
+    ("mg", "+{line} {file}"),
+    ("micro", "{file}:{line}:{column}"),
+    ("nano", "+{line},{column} {file}"),
+    ("ne", "+{line},{column} {file}"),
+    ("nvim", "+{line} {file}"),
+    ("subl", "{file}:{line}:{column}"),
+    ("vi", "+{line} {file}"),
+    ("vim", "+{line} {file}"),
+    ("zed", "{file}:{line}:{column}"),
+];
+
+const fn get_system_opener() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    }
+}
+
+fn get_editor() -> String {
+    Config::from_config_file()
+        .editor
+        .or_else(|| var("VISUAL").ok())
+        .or_else(|| var("EDITOR").ok())
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| get_system_opener().to_string())
+}
+
+fn get_pdf_viewer() -> String {
+    Config::from_config_file()
+        .pdf_viewer
+        .filter(|pdf_viewer| !pdf_viewer.trim().is_empty())
+        .unwrap_or_else(|| get_system_opener().to_string())
+}
+
+/// Splits `template` like a shell would, keeping quoted arguments together.
+fn split_arguments(template: &str) -> Vec<String> {
+    let mut arguments = vec![];
+    let mut argument = String::new();
+    let mut quote = None;
+    let mut is_argument = false;
+
+    for char in template.chars() {
+        match (quote, char) {
+            (Some(open), char) if char == open => quote = None,
+            (Some(_), char) => argument.push(char),
+            (None, '"' | '\'') => {
+                quote = Some(char);
+                is_argument = true;
+            }
+            (None, char) if char.is_whitespace() => {
+                if is_argument {
+                    arguments.push(argument.clone());
+                    argument.clear();
+                    is_argument = false;
+                }
+            }
+            (None, char) => {
+                argument.push(char);
+                is_argument = true;
+            }
+        }
+    }
+
+    if is_argument {
+        arguments.push(argument);
+    }
+
+    arguments
+}
+
+/// Fills in the `{file}`, `{line}` and `{column}` placeholders of
+/// `template`, appending the file when the template doesn't mention it.
+fn get_command(
+    template: &str,
+    file: &Path,
+    line: Option<usize>,
+    column: Option<usize>,
+) -> Vec<String> {
+    let file = file.to_str().expect("Failed to parse file path.");
+    let line = line.unwrap_or(1).to_string();
+    let column = column.unwrap_or(1).to_string();
+    let mut command: Vec<String> = split_arguments(template)
+        .iter()
+        .map(|argument| {
+            argument
+                .replace("{file}", file)
+                .replace("{line}", &line)
+                .replace("{column}", &column)
+        })
+        .collect();
+
+    if !template.contains("{file}") {
+        command.push(file.to_string());
+    }
+
+    command
+}
+
+fn get_program(arguments: &[String]) -> Option<&str> {
+    arguments.first().and_then(|program| {
+        Path::new(program)
+            .file_name()
+            .and_then(|name| name.to_str())
+    })
+}
+
+/// `editor` with its known position arguments (or else `fallback`) appended,
+/// unless it already places the file itself.
+fn get_positioned_editor(editor: &str, fallback: Option<&str>) -> String {
+    if ["{file}", "{line}", "{column}"]
+        .iter()
+        .any(|placeholder| editor.contains(placeholder))
+    {
+        return editor.to_string();
+    }
+
+    let arguments = split_arguments(editor);
+
+    get_program(&arguments)
+        .and_then(|program| {
+            EDITOR_POSITIONS
+                .iter()
+                .find(|(name, _)| *name == program)
+                .map(|(_, position)| *position)
+        })
+        .or(fallback)
+        .map_or_else(
+            || editor.to_string(),
+            |position| format!("{editor} {position}"),
+        )
+}
+
+fn is_terminal_editor(template: &str) -> bool {
+    if let Some(is_terminal) = Config::from_config_file().editor_is_terminal {
+        return is_terminal;
+    }
+
+    let arguments = split_arguments(template);
+
+    let Some(program) = get_program(&arguments) else {
+        return false;
+    };
+
+    TERMINAL_EDITORS.iter().any(|editor| {
+        let mut editor = editor.split(' ');
+
+        editor.next() == Some(program)
+            && editor
+                .all(|flag| arguments.iter().any(|argument| argument == flag))
+    })
+}
+
+/// Runs `command` in the current terminal, waiting for it to exit.
+fn run(command: &[String]) {
+    let Some((program, arguments)) = command.split_first() else {
+        return;
+    };
+
+    if let Err(err) = Command::new(program).args(arguments).status() {
+        println!("Failed to run `{program}` ({err})");
+    }
+}
+
+/// Starts `command` in its own process group with no terminal attached, so
+/// that it outlives thoth and ignores its interrupts.
+fn spawn_detached(command: &[String]) {
+    let Some((program, arguments)) = command.split_first() else {
+        return;
+    };
+
+    if let Err(err) = Command::new(program)
+        .args(arguments)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .process_group(0)
+        .spawn()
+    {
+        println!("Failed to run `{program}` ({err})");
+    }
+}
+
+pub fn uses_terminal_editor() -> bool {
+    is_terminal_editor(&get_editor())
+}
+
+/// Opens `file` in the editor, blocking until a terminal editor exits.
+pub fn open_in_editor(file: &Path, line: Option<usize>) {
+    open_in_editor_at(file, line, None);
+}
+
+pub fn open_in_editor_at(
+    file: &Path,
+    line: Option<usize>,
+    column: Option<usize>,
+) {
+    open_in_editor_with_fallback(file, line, column, None);
+}
+
+/// Like `open_in_editor_at`, with `fallback` position arguments for editors
+/// that aren't known, except the system opener, which can't take any.
+pub fn open_in_editor_with_fallback(
+    file: &Path,
+    line: Option<usize>,
+    column: Option<usize>,
+    fallback: Option<&str>,
+) {
+    let editor = get_editor();
+    let fallback = fallback.filter(|_| editor != get_system_opener());
+    let command = get_command(
+        &get_positioned_editor(&editor, fallback),
+        file,
+        line,
+        column,
+    );
+
+    if is_terminal_editor(&editor) {
+        run(&command);
+    } else {
+        spawn_detached(&command);
+    }
+}
+
+pub fn open_in_pdf_viewer(file: &Path) {
+    spawn_detached(&get_command(&get_pdf_viewer(), file, None, None));
+}
+
+/// Opens `file` with the desktop's default application for its type.
+pub fn open_with_system_opener(file: &Path) {
+    spawn_detached(&get_command(get_system_opener(), file, None, None));
+}
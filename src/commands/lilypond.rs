@@ -6,27 +6,34 @@ pub mod uninstall;
 
 use std::fmt;
 use std::fmt::{Display, Formatter};
+use std::process::Command;
 
 use itertools::{
     EitherOrBoth::{Both, Left, Right},
     Itertools,
 };
+use once_cell::sync::Lazy;
 use owo_colors::OwoColorize;
 use regex::Regex;
 
-use self::global::global;
+use self::global::{global, read_global_version};
 use self::install::install;
-use self::list::list;
+use self::list::{get_installed_versions, list};
 use self::list_remote::{list_remote, LilypondReleases};
 use self::uninstall::uninstall;
 use super::table;
-use super::{LilypondCommand, VersionStability};
+use super::{LilypondCommand, OutputFormat, VersionStability};
 
 static GLOBAL_PATH: &str = "~/.thoth-versions";
 static INSTALL_PATH: &str = "~/.local/share/thoth";
 static GITLAB_URL: &str =
     "https://gitlab.com/api/v4/projects/18695663/releases";
 
+static VERSION_NUMBER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\d+\.\d+\.\d+")
+        .expect("Failed to create lilypond version regex.")
+});
+
 impl Display for VersionStability {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         let display = format!("{self:?}").to_lowercase();
@@ -56,16 +63,16 @@ fn get_version_stability(
             _ => Err("invalid version specifier"),
         }
     } else {
-        let err = "Failed to parse lilypond minor version.";
+        let err = "invalid version number";
         let minor_version = version
             .split('.')
             .enumerate()
             .filter(|(index, _)| index == &1usize)
             .map(|(_, value)| value)
             .next()
-            .expect(err)
+            .ok_or(err)?
             .parse::<i32>()
-            .expect(err);
+            .map_err(|_| err)?;
         if minor_version % 2 == 0 {
             Ok(VersionStability::Stable)
         } else {
@@ -74,6 +81,59 @@ fn get_version_stability(
     }
 }
 
+pub fn parse_version_number(text: &str) -> Option<String> {
+    VERSION_NUMBER
+        .find(text)
+        .map(|version| version.as_str().to_string())
+}
+
+fn get_path_version() -> Option<String> {
+    let output = Command::new("lilypond").arg("--version").output().ok()?;
+
+    parse_version_number(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn resolve_version_specifier(
+    version: &str,
+    installed_versions: &[String],
+) -> Option<String> {
+    if is_latest_version(version) {
+        let stability = get_version_stability(version).ok()?;
+
+        installed_versions
+            .iter()
+            .find(|installed_version| {
+                get_version_stability(installed_version).as_ref()
+                    == Ok(&stability)
+            })
+            .map(ToString::to_string)
+    } else {
+        parse_version_number(version)
+    }
+}
+
+/// Resolve a version number without network access: an explicit version
+/// first, otherwise the global version, the newest installed version, or
+/// the lilypond on PATH, in that order. "latest-stable" and
+/// "latest-unstable" resolve to the newest matching installed version.
+pub fn resolve_lilypond_version(
+    lilypond_version: &Option<String>,
+) -> Option<String> {
+    let installed_versions = get_installed_versions();
+
+    if let Some(version) = lilypond_version {
+        return resolve_version_specifier(version, &installed_versions);
+    }
+
+    read_global_version()
+        .ok()
+        .and_then(|version| {
+            resolve_version_specifier(version.trim(), &installed_versions)
+        })
+        .or_else(|| installed_versions.first().map(ToString::to_string))
+        .or_else(get_path_version)
+}
+
 pub fn get_tag_names() -> Vec<String> {
     let mut releases = vec![];
     let err = "Failed to parse lilypond release.";
@@ -113,6 +173,7 @@ pub fn list_versions(
     mut versions: Vec<String>,
     version_regex: &Option<String>,
     stability: &Option<VersionStability>,
+    format: &Option<OutputFormat>,
 ) {
     if let Some(stability) = stability {
         versions = versions
@@ -177,7 +238,7 @@ pub fn list_versions(
         }
     }
 
-    table::print(&titles, rows);
+    table::print(&titles, rows, format);
 }
 
 pub fn main(version: &Option<String>, command: &Option<LilypondCommand>) {
@@ -198,11 +259,13 @@ pub fn main(version: &Option<String>, command: &Option<LilypondCommand>) {
             LilypondCommand::List {
                 version_regex,
                 stability,
-            } => list(version_regex, stability),
+                format,
+            } => list(version_regex, stability, format),
             LilypondCommand::ListRemote {
                 version_regex,
                 stability,
-            } => list_remote(version_regex, stability),
+                format,
+            } => list_remote(version_regex, stability, format),
         },
     );
 }
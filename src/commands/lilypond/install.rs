@@ -105,7 +105,7 @@ fn download_asset(asset_link: AssetLink) {
     create_dir_all(&install_path)
         .expect("Failed to create lilypond installations folder. ");
 
-    println!("Downloading {}...", asset_link.direct_asset_url);
+    eprintln!("Downloading {}...", asset_link.direct_asset_url);
 
     let err = "Failed to download lilypond.";
     let content = get(asset_link.direct_asset_url)
@@ -122,7 +122,7 @@ fn download_asset(asset_link: AssetLink) {
         File::open(&file_path).expect("Failed to read lilypond zip."),
     ));
 
-    println!("Unpacking {}...", asset_link.name);
+    eprintln!("Unpacking {}...", asset_link.name);
 
     archive
         .unpack(install_path)
@@ -138,14 +138,14 @@ pub fn install(version: &Option<String>) -> io::Result<()> {
     };
 
     if !is_valid_version(&value) {
-        println!("invalid version specifier");
+        eprintln!("invalid version specifier");
 
         return Ok(());
     }
 
     get_asset_link(&value).map_or_else(
         || {
-            println!("No assets found.");
+            eprintln!("No assets found.");
         },
         |asset_link| {
             download_asset(asset_link);
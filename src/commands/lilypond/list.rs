@@ -3,24 +3,28 @@ use std::fs::read_dir;
 use human_sort::sort;
 use shellexpand::tilde;
 
-use super::list_versions;
-use crate::commands::{lilypond::INSTALL_PATH, VersionStability};
+use super::{list_versions, parse_version_number};
+use crate::commands::{
+    lilypond::INSTALL_PATH, OutputFormat, VersionStability,
+};
 
-pub fn list(
-    version_regex: &Option<String>,
-    stability: &Option<VersionStability>,
-) {
+pub fn get_installed_versions() -> Vec<String> {
     let install_path = tilde(INSTALL_PATH).to_string();
-    let err = "Failed to parse installed lilypond version.";
-
-    let versions: Vec<String> = read_dir(&install_path)
-        .expect(err)
-        .map(|path| {
-            path.expect(err)
-                .path()
-                .display()
-                .to_string()
-                .replace(&format!("{}/lilypond-", &install_path), "")
+    let prefix = format!("{install_path}/lilypond-");
+
+    let Ok(entries) = read_dir(&install_path) else {
+        return vec![];
+    };
+
+    // Skip stray entries such as "lilypond-2" or unrelated directories.
+    let versions: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path().display().to_string();
+            let version = path.strip_prefix(&prefix)?;
+
+            (parse_version_number(version).as_deref() == Some(version))
+                .then(|| version.to_string())
         })
         .collect();
 
@@ -29,8 +33,13 @@ pub fn list(
 
     sort(&mut versions);
 
-    let versions: Vec<String> =
-        versions.iter().rev().map(ToString::to_string).collect();
+    versions.iter().rev().map(ToString::to_string).collect()
+}
 
-    list_versions(versions, version_regex, stability);
+pub fn list(
+    version_regex: &Option<String>,
+    stability: &Option<VersionStability>,
+    format: &Option<OutputFormat>,
+) {
+    list_versions(get_installed_versions(), version_regex, stability, format);
 }
@@ -1,7 +1,9 @@
 use owo_colors::OwoColorize;
 use serde::Deserialize;
 
-use super::{get_versions, list_versions, VersionStability, GITLAB_URL};
+use super::{
+    get_versions, list_versions, OutputFormat, VersionStability, GITLAB_URL,
+};
 
 #[derive(Debug, Deserialize)]
 pub struct DirectAssetUrl {
@@ -84,11 +86,12 @@ impl Iterator for LilypondReleases {
 pub fn list_remote(
     version_regex: &Option<String>,
     stability: &Option<VersionStability>,
+    format: &Option<OutputFormat>,
 ) {
     let versions: Vec<String> = get_versions()
         .iter()
         .map(|release| release.bold().to_string())
         .collect();
 
-    list_versions(versions, version_regex, stability);
+    list_versions(versions, version_regex, stability, format);
 }
@@ -1,16 +1,20 @@
+use std::path::Path;
+
 use glob::glob;
 use owo_colors::OwoColorize;
 use titlecase::titlecase;
 
-use super::compile::is_compiled;
+use super::compile::FileFormat;
 use super::get_pdfs_directory_from_arg;
-use crate::commands::scores::search;
+use crate::commands::scores::{get_part_files, get_score_ly_file, search};
 use crate::commands::table;
+use crate::commands::OutputFormat;
 
 struct Composition {
     artist: String,
     title: String,
     is_compiled: bool,
+    outputs: Vec<&'static str>,
 }
 
 fn get_display(value: &str) -> String {
@@ -27,7 +31,12 @@ impl Composition {
             "missing".red().to_string()
         };
 
-        vec![artist.yellow().to_string(), title.bold().to_string(), pdf]
+        vec![
+            artist.yellow().to_string(),
+            title.bold().to_string(),
+            pdf,
+            self.outputs.join(", "),
+        ]
     }
 }
 
@@ -49,36 +58,52 @@ fn remove_leading_articles(mut value: String) -> String {
     value
 }
 
+pub struct ListOptions {
+    pub outdated: bool,
+    pub compiled: bool,
+    pub search_artist: bool,
+    pub search_title: bool,
+}
+
 pub fn main(
     search_terms: &Vec<String>,
-    outdated: bool,
-    compiled: bool,
-    search_artist: bool,
-    search_title: bool,
+    options: &ListOptions,
     scores_directory: &Option<String>,
     pdfs_directory: &Option<String>,
+    format: &Option<OutputFormat>,
 ) {
     let mut compositions = vec![];
 
+    let ListOptions {
+        outdated,
+        compiled,
+        search_artist,
+        search_title,
+    } = *options;
+
     let found_scores =
         search(search_terms, search_artist, search_title, scores_directory);
 
     for score in found_scores {
-        let mut pdf = false;
-        let err = "Failed to parse score file name.";
-        let path =
-            String::from(score.file_name().expect(err).to_str().expect(err));
         let pdfs_directory = get_pdfs_directory_from_arg(pdfs_directory);
-        let pattern = format!("{pdfs_directory}/{path}*.pdf");
-
-        for pdf_file in glob(&pattern)
-            .expect("Failed to read glob pattern")
-            .flatten()
-        {
-            if is_compiled(&score, &pdf_file) {
-                pdf = true;
-                break;
-            }
+        let ly_file = get_score_ly_file(&score.display().to_string());
+        let mut outputs: Vec<&'static str> =
+            ly_file.map_or_else(Vec::new, |ly_file| {
+                FileFormat::ALL
+                    .into_iter()
+                    .filter(|format| {
+                        format
+                            .is_compiled(Path::new(&ly_file), &pdfs_directory)
+                    })
+                    .map(FileFormat::get_extension)
+                    .collect()
+            });
+        let pdf = outputs.contains(&FileFormat::Pdf.get_extension());
+
+        if get_part_files(&score).iter().any(|part_file| {
+            FileFormat::Pdf.is_compiled(part_file, &pdfs_directory)
+        }) {
+            outputs.push("parts");
         }
 
         let should_display =
@@ -117,6 +142,7 @@ pub fn main(
                 artist,
                 title,
                 is_compiled: pdf,
+                outputs,
             });
         }
     }
@@ -137,6 +163,7 @@ pub fn main(
             "Artist".italic().to_string(),
             "Title".italic().to_string(),
             "Status".italic().to_string(),
+            "Outputs".italic().to_string(),
         ];
 
         let rows = compositions
@@ -144,6 +171,6 @@ pub fn main(
             .map(Composition::get_row_values)
             .collect();
 
-        table::print(&header, rows);
+        table::print(&header, rows, format);
     }
 }
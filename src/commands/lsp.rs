@@ -0,0 +1,525 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::{canonicalize, create_dir_all, read_to_string};
+use std::io::{stdin, stdout, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use glob::glob;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::{json, Value};
+
+use super::compile::run_lilypond;
+use super::get_scores_directory_from_arg;
+use super::helpers::get_helper_file_names;
+use super::includes::{
+    get_dependencies, get_dependent_scores, resolve_include,
+};
+use super::scores::TEMPORARY_DIRECTORY;
+use super::textedit::decode_percent_escapes;
+
+static INCLUDE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\\include\s+"([^"]*)""#)
+        .expect("Failed to create include regex.")
+});
+
+static INCLUDE_PREFIX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\\include\s+"([^"]*)$"#)
+        .expect("Failed to create include prefix regex.")
+});
+
+static MESSAGE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?m)^(.+?):(\d+):(\d+): (error|warning|programming error): (.+)$",
+    )
+    .expect("Failed to create lilypond message regex.")
+});
+
+static HEADER_FIELD: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\s*([a-z]+)\s*=")
+        .expect("Failed to create header field regex.")
+});
+
+const HEADER_FIELDS: [(&str, &str); 14] = [
+    ("arranger", "Printed flush right below the composer."),
+    ("composer", "Printed flush right below the subtitles."),
+    ("copyright", "Printed at the bottom of the first page."),
+    ("dedication", "Printed centered above the title."),
+    ("instrument", "Printed centered at the top of every page."),
+    ("lyricist", "Printed flush left below the subtitles."),
+    ("meter", "Printed flush left below the poet."),
+    ("opus", "Printed flush right below the arranger."),
+    ("piece", "Printed flush left above the score."),
+    ("poet", "Printed flush left below the subtitles."),
+    ("subsubtitle", "Printed centered below the subtitle."),
+    ("subtitle", "Printed centered below the title."),
+    ("tagline", "Printed at the bottom of the last page."),
+    ("title", "Printed centered at the top of the first page."),
+];
+
+const FILE_COMPLETION_KIND: u8 = 17;
+
+const ERROR_SEVERITY: u8 = 1;
+
+const WARNING_SEVERITY: u8 = 2;
+
+fn read_message(reader: &mut impl BufRead) -> Option<Value> {
+    let mut length = None;
+
+    loop {
+        let mut header = String::new();
+
+        if reader.read_line(&mut header).ok()? == 0 {
+            return None;
+        }
+
+        let header = header.trim();
+
+        if header.is_empty() {
+            break;
+        }
+
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = value.trim().parse().ok();
+        }
+    }
+
+    let mut body = vec![0; length?];
+    reader.read_exact(&mut body).ok()?;
+
+    serde_json::from_slice(&body).ok()
+}
+
+fn write_message(message: &Value) {
+    let body = message.to_string();
+    let mut stdout = stdout().lock();
+
+    let _ = write!(stdout, "Content-Length: {}\r\n\r\n{body}", body.len())
+        .and_then(|()| stdout.flush());
+}
+
+fn get_path(uri: &str) -> PathBuf {
+    PathBuf::from(decode_percent_escapes(
+        uri.strip_prefix("file://").unwrap_or(uri),
+    ))
+}
+
+fn get_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+
+    for byte in path.display().to_string().bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+            uri.push(char::from(byte));
+        } else {
+            uri.push_str(&format!("%{byte:02X}"));
+        }
+    }
+
+    uri
+}
+
+fn get_line(text: &str, line: usize) -> &str {
+    text.lines().nth(line).unwrap_or_default()
+}
+
+/// The byte index of the `character`th character of `line`.
+fn get_index(line: &str, character: usize) -> usize {
+    line.char_indices()
+        .nth(character)
+        .map_or(line.len(), |(index, _)| index)
+}
+
+fn get_character(line: &str, index: usize) -> usize {
+    line[..index].chars().count()
+}
+
+fn is_identifier_char(char: char) -> bool {
+    char.is_alphanumeric() || char == '_' || char == '-'
+}
+
+/// The identifier at `index` in `line`, and whether it is a `\command`.
+fn get_word(line: &str, index: usize) -> Option<(&str, bool)> {
+    let start = line[..index]
+        .char_indices()
+        .rev()
+        .take_while(|(_, char)| is_identifier_char(*char))
+        .last()
+        .map_or(index, |(start, _)| start);
+    let end = line[index..]
+        .char_indices()
+        .find(|(_, char)| !is_identifier_char(*char))
+        .map_or(line.len(), |(end, _)| index + end);
+    let word = &line[start..end];
+
+    if word.is_empty() {
+        None
+    } else {
+        Some((word, line[..start].ends_with('\\')))
+    }
+}
+
+fn get_range(line: usize, start: usize, end: usize) -> Value {
+    json!({
+        "start": { "line": line, "character": start },
+        "end": { "line": line, "character": end },
+    })
+}
+
+/// The scores to compile to check `file`: itself if it is a score, or the
+/// scores next to it that include it.
+fn get_scores_to_check(file: &Path, scores_directory: &str) -> Vec<PathBuf> {
+    if file.extension().is_some_and(|extension| extension == "ly") {
+        return vec![file.to_path_buf()];
+    }
+
+    let Some(directory) = file.parent() else {
+        return vec![];
+    };
+
+    let scores: Vec<PathBuf> = glob(&format!("{}/*.ly", directory.display()))
+        .expect("Failed to read glob pattern")
+        .flatten()
+        .collect();
+
+    get_dependent_scores(&[file.to_path_buf()], &scores, scores_directory)
+}
+
+struct Server {
+    lilypond_version: Option<String>,
+    scores_directory: String,
+    documents: HashMap<String, String>,
+    diagnostics: BTreeSet<String>,
+}
+
+impl Server {
+    fn get_text(&self, uri: &str) -> String {
+        self.documents.get(uri).cloned().unwrap_or_else(|| {
+            read_to_string(get_path(uri)).unwrap_or_default()
+        })
+    }
+
+    /// The document and line at the position in `params`, with the byte
+    /// index of the cursor in that line.
+    fn get_position(&self, params: &Value) -> (String, String, usize, usize) {
+        let uri = params["textDocument"]["uri"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let line_number =
+            params["position"]["line"].as_u64().unwrap_or_default() as usize;
+        let character = params["position"]["character"]
+            .as_u64()
+            .unwrap_or_default() as usize;
+        let line = get_line(&self.get_text(&uri), line_number).to_string();
+        let index = get_index(&line, character);
+
+        (uri, line, line_number, index)
+    }
+
+    fn get_helper_includes(&self) -> BTreeSet<String> {
+        let mut includes: BTreeSet<String> = get_helper_file_names()
+            .iter()
+            .map(|name| format!("helpers/{name}"))
+            .collect();
+
+        includes.extend(
+            glob(&format!("{}/helpers/*.ily", self.scores_directory))
+                .expect("Failed to read glob pattern")
+                .flatten()
+                .filter_map(|file| {
+                    file.file_name().map(|name| {
+                        format!("helpers/{}", name.to_string_lossy())
+                    })
+                }),
+        );
+
+        includes
+    }
+
+    fn complete(&self, params: &Value) -> Value {
+        let (_, line, line_number, index) = self.get_position(params);
+
+        let Some(captures) = INCLUDE_PREFIX.captures(&line[..index]) else {
+            return json!([]);
+        };
+
+        let end = get_character(&line, index);
+        let start = end - captures[1].chars().count();
+
+        self.get_helper_includes()
+            .iter()
+            .map(|include| {
+                json!({
+                    "label": include,
+                    "kind": FILE_COMPLETION_KIND,
+                    "textEdit": {
+                        "range": get_range(line_number, start, end),
+                        "newText": include,
+                    },
+                })
+            })
+            .collect()
+    }
+
+    /// The file and line where `\name` is assigned, searching `uri` and the
+    /// files of the scores it belongs to.
+    fn find_variable(
+        &self,
+        name: &str,
+        uri: &str,
+    ) -> Option<(PathBuf, usize)> {
+        let definition =
+            Regex::new(&format!(r"(?m)^{}\s*=", regex::escape(name))).ok()?;
+        let file = get_path(uri);
+        let text = self.get_text(uri);
+
+        if let Some(found) = definition.find(&text) {
+            return Some((file, text[..found.start()].matches('\n').count()));
+        }
+
+        get_scores_to_check(&file, &self.scores_directory)
+            .iter()
+            .flat_map(|score| get_dependencies(score, &self.scores_directory))
+            .collect::<BTreeSet<PathBuf>>()
+            .into_iter()
+            .find_map(|dependency| {
+                let text = read_to_string(&dependency).ok()?;
+                let found = definition.find(&text)?;
+
+                Some((dependency, text[..found.start()].matches('\n').count()))
+            })
+    }
+
+    fn find_definition(&self, params: &Value) -> Value {
+        let (uri, line, _, index) = self.get_position(params);
+
+        for captures in INCLUDE.captures_iter(&line) {
+            let include = captures.get(1).expect("Failed to parse include.");
+
+            if (include.start()..=include.end()).contains(&index) {
+                let file = get_path(&uri);
+                let directory =
+                    file.parent().unwrap_or_else(|| Path::new("/"));
+
+                return resolve_include(
+                    include.as_str(),
+                    directory,
+                    Path::new(&self.scores_directory),
+                )
+                .map_or(Value::Null, |file| {
+                    json!({ "uri": get_uri(&file), "range": get_range(0, 0, 0) })
+                });
+            }
+        }
+
+        let Some((name, true)) = get_word(&line, index) else {
+            return Value::Null;
+        };
+
+        self.find_variable(name, &uri)
+            .map_or(Value::Null, |(file, line)| {
+                json!({
+                    "uri": get_uri(&file),
+                    "range": get_range(line, 0, name.chars().count()),
+                })
+            })
+    }
+
+    fn hover(&self, params: &Value) -> Value {
+        let (_, line, _, index) = self.get_position(params);
+
+        let Some((field, false)) = get_word(&line, index) else {
+            return Value::Null;
+        };
+
+        if !HEADER_FIELD
+            .captures(&line)
+            .is_some_and(|captures| &captures[1] == field)
+        {
+            return Value::Null;
+        }
+
+        HEADER_FIELDS
+            .iter()
+            .find(|(name, _)| *name == field)
+            .map_or(Value::Null, |(name, description)| {
+                json!({
+                    "contents": {
+                        "kind": "markdown",
+                        "value": format!("**{name}**\n\n{description}"),
+                    },
+                })
+            })
+    }
+
+    /// Compiles the scores that use `uri` and publishes their errors,
+    /// clearing files whose errors have been fixed.
+    fn publish_diagnostics(&mut self, uri: &str) {
+        let file = get_path(uri);
+        let output_directory = format!("{TEMPORARY_DIRECTORY}/lsp");
+        let mut diagnostics: BTreeMap<String, Vec<Value>> = BTreeMap::new();
+
+        create_dir_all(&output_directory)
+            .expect("Failed to create language server directory.");
+
+        for score in get_scores_to_check(&file, &self.scores_directory) {
+            let (Ok(output) | Err(output)) = run_lilypond(
+                &score,
+                &self.lilypond_version,
+                &["-dno-print-pages"],
+                &self.scores_directory,
+                &output_directory,
+            );
+
+            let directory = score.parent().unwrap_or_else(|| Path::new("/"));
+
+            for captures in MESSAGE.captures_iter(&output) {
+                let path = Path::new(&captures[1]);
+                let Ok(path) = canonicalize(path)
+                    .or_else(|_| canonicalize(directory.join(path)))
+                else {
+                    continue;
+                };
+                let line = captures[2].parse::<usize>().unwrap_or(1);
+                let column = captures[3].parse::<usize>().unwrap_or(1);
+                let severity = if &captures[4] == "warning" {
+                    WARNING_SEVERITY
+                } else {
+                    ERROR_SEVERITY
+                };
+
+                diagnostics.entry(get_uri(&path)).or_default().push(json!({
+                    "range": get_range(
+                        line.saturating_sub(1),
+                        column.saturating_sub(1),
+                        column,
+                    ),
+                    "severity": severity,
+                    "source": "lilypond",
+                    "message": &captures[5],
+                }));
+            }
+        }
+
+        for uri in self.diagnostics.iter().chain([&uri.to_string()]) {
+            diagnostics.entry(uri.to_string()).or_default();
+        }
+
+        self.diagnostics = diagnostics
+            .iter()
+            .filter(|(_, diagnostics)| !diagnostics.is_empty())
+            .map(|(uri, _)| uri.to_string())
+            .collect();
+
+        for (uri, diagnostics) in diagnostics {
+            write_message(&json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/publishDiagnostics",
+                "params": { "uri": uri, "diagnostics": diagnostics },
+            }));
+        }
+    }
+
+    fn handle_request(&self, method: &str, params: &Value) -> Option<Value> {
+        match method {
+            "initialize" => Some(json!({
+                "capabilities": {
+                    "textDocumentSync": {
+                        "openClose": true,
+                        "change": 1,
+                        "save": true,
+                    },
+                    "completionProvider": {
+                        "triggerCharacters": ["\"", "/"],
+                    },
+                    "definitionProvider": true,
+                    "hoverProvider": true,
+                },
+                "serverInfo": {
+                    "name": "thoth",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            })),
+            "shutdown" => Some(Value::Null),
+            "textDocument/completion" => Some(self.complete(params)),
+            "textDocument/definition" => Some(self.find_definition(params)),
+            "textDocument/hover" => Some(self.hover(params)),
+            _ => None,
+        }
+    }
+
+    fn handle_notification(&mut self, method: &str, params: &Value) {
+        let uri = params["textDocument"]["uri"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+
+        match method {
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string();
+
+                self.documents.insert(uri, text);
+            }
+            "textDocument/didChange" => {
+                if let Some(text) = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str())
+                {
+                    self.documents.insert(uri, text.to_string());
+                }
+            }
+            "textDocument/didSave" => self.publish_diagnostics(&uri),
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn main(
+    lilypond_version: &Option<String>,
+    scores_directory: &Option<String>,
+) {
+    let mut server = Server {
+        lilypond_version: lilypond_version.clone(),
+        scores_directory: get_scores_directory_from_arg(scores_directory),
+        documents: HashMap::new(),
+        diagnostics: BTreeSet::new(),
+    };
+    let mut reader = stdin().lock();
+
+    while let Some(message) = read_message(&mut reader) {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+
+        if method == "exit" {
+            break;
+        }
+
+        let Some(id) = message.get("id") else {
+            server.handle_notification(method, params);
+            continue;
+        };
+
+        if method.is_empty() {
+            continue;
+        }
+
+        let response = server.handle_request(method, params).map_or_else(
+            || {
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": -32601, "message": "Method not found" },
+                })
+            },
+            |result| json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        );
+
+        write_message(&response);
+    }
+}
@@ -1,23 +1,26 @@
-use std::{
-    path::{Path, PathBuf},
-    process::Command,
-};
+use std::path::{Path, PathBuf};
 
 use super::{
-    scores::{get_found_ly_files, get_found_pdfs, get_selected_items},
+    compile::FileFormat,
+    launcher::{open_in_editor, open_in_pdf_viewer, open_with_system_opener},
+    scores::{
+        get_found_ly_files, get_found_outputs, get_found_pdfs,
+        get_selected_items,
+    },
     ScoreFileType,
 };
 
 pub fn open_file(file_path: &Path) {
-    let file_path = file_path
-        .to_str()
-        .expect("Failed to parse file path.")
-        .to_string();
-    Command::new("open")
-        .arg(&file_path)
-        .output()
-        .expect("Failed to run `open` command.");
-    println!("Opened {file_path}");
+    match file_path
+        .extension()
+        .and_then(|extension| extension.to_str())
+    {
+        Some("pdf") => open_in_pdf_viewer(file_path),
+        Some("ly" | "ily") => open_in_editor(file_path, None),
+        _ => open_with_system_opener(file_path),
+    }
+
+    println!("Opened {}", file_path.display());
 }
 
 pub fn main(
@@ -63,6 +66,23 @@ pub fn main(
             scores_directory,
             pdfs_directory,
         ),
+        Some(file_type) => {
+            let format = match file_type {
+                ScoreFileType::Png => FileFormat::Png,
+                ScoreFileType::Svg => FileFormat::Svg,
+                ScoreFileType::Midi => FileFormat::Midi,
+                _ => FileFormat::Eps,
+            };
+
+            get_found_outputs(
+                search_terms,
+                search_artist,
+                search_title,
+                scores_directory,
+                pdfs_directory,
+                format,
+            )
+        }
     };
 
     if !use_all_matches && matching_files.len() > 1 {
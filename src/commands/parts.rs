@@ -0,0 +1,335 @@
+use std::fs::{create_dir_all, read_to_string, write};
+use std::path::{Path, PathBuf};
+
+use glob::glob;
+use indoc::formatdoc;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::compile::{compile_input_file, CompileOptions};
+use super::scores::get_selected_ly_file;
+use super::templates::ensemble::indent;
+use super::transpose::{update_header, update_includes};
+
+/// Part files are written to this subdirectory of the score, and compile to
+/// `{stem}-{instrument}` outputs alongside the score's own.
+pub const PARTS_DIRECTORY: &str = "parts";
+
+static STAFF: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\\new\s+\w*Staff(?:\s*=\s*"[^"]*")?\s*"#)
+        .expect("Failed to create staff regex.")
+});
+
+static INSTRUMENT_NAME: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\binstrumentName\s*=\s*"([^"]*)""#)
+        .expect("Failed to create instrument name regex.")
+});
+
+static INSTRUMENT_NAMES: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?m)^\s*(?:short)?[iI]nstrumentName\s*=\s*"[^"]*"\s*\n"#)
+        .expect("Failed to create instrument names regex.")
+});
+
+static EMPTY_WITH: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\\with\s*\{\s*\}\s*")
+        .expect("Failed to create empty with regex.")
+});
+
+static SCORE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\\score\s*\{").expect("Failed to create score regex.")
+});
+
+static BLANK_LINES: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\n{3,}").expect("Failed to create blank lines regex.")
+});
+
+struct Part {
+    instrument: String,
+    staff: String,
+}
+
+fn skip_whitespace(content: &str, position: usize) -> usize {
+    content.len() - content[position..].trim_start().len()
+}
+
+/// The position after the delimiter that closes the one at `start`.
+fn get_balanced_end(
+    content: &str,
+    start: usize,
+    open: &str,
+    close: &str,
+) -> usize {
+    let mut depth = 0;
+    let mut position = start;
+
+    while position < content.len() {
+        let rest = &content[position..];
+
+        if rest.starts_with(open) {
+            depth += 1;
+            position += open.len();
+        } else if rest.starts_with(close) {
+            depth -= 1;
+            position += close.len();
+
+            if depth == 0 {
+                return position;
+            }
+        } else {
+            position += rest.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+
+    content.len()
+}
+
+/// The end of the music expression at `start`: a `{ }` or `<< >>` block, or
+/// a command such as `\piano_upper`, `\drummode { }` or `\relative c' { }`.
+pub fn get_expression_end(content: &str, start: usize) -> usize {
+    let start = skip_whitespace(content, start);
+    let rest = &content[start..];
+
+    if rest.starts_with("<<") {
+        get_balanced_end(content, start, "<<", ">>")
+    } else if rest.starts_with('{') {
+        get_balanced_end(content, start, "{", "}")
+    } else if let Some(command) = rest.strip_prefix('\\') {
+        let length = command
+            .find(|char: char| {
+                !(char.is_alphanumeric() || char == '_' || char == '-')
+            })
+            .map_or(rest.len(), |length| length + 1);
+        let arguments = &content[start + length..];
+        let line =
+            &arguments[..arguments.find('\n').unwrap_or(arguments.len())];
+
+        match line.find(['{', '<']) {
+            Some(block)
+                if !line[..block].contains('\\')
+                    && (line[block..].starts_with('{')
+                        || line[block..].starts_with("<<")) =>
+            {
+                get_expression_end(content, start + length + block)
+            }
+            _ => start + length,
+        }
+    } else {
+        start + rest.find(char::is_whitespace).unwrap_or(rest.len())
+    }
+}
+
+fn get_column(content: &str, position: usize) -> usize {
+    content[..position]
+        .rsplit('\n')
+        .next()
+        .map_or(0, |line| line.chars().count())
+}
+
+fn dedent(text: &str) -> String {
+    let indentation = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or_default();
+
+    text.lines()
+        .map(|line| line.get(indentation..).unwrap_or_default())
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+/// Every staff with an `instrumentName`, skipping the staves nested in one,
+/// such as the staves of a `PianoStaff`.
+fn get_parts(content: &str) -> Vec<Part> {
+    let mut parts = vec![];
+    let mut last_end = 0;
+
+    for staff in STAFF.find_iter(content) {
+        if staff.start() < last_end {
+            continue;
+        }
+
+        let mut position = staff.end();
+        let mut instrument = None;
+
+        if content[position..].starts_with("\\with") {
+            let with_start =
+                skip_whitespace(content, position + "\\with".len());
+            let with_end = get_balanced_end(content, with_start, "{", "}");
+
+            instrument = INSTRUMENT_NAME
+                .captures(&content[with_start..with_end])
+                .map(|captures| captures[1].to_string());
+            position = with_end;
+        }
+
+        let Some(instrument) = instrument else {
+            continue;
+        };
+
+        let end = get_expression_end(content, position);
+        let column = get_column(content, staff.start());
+        let staff_text =
+            format!("{}{}", " ".repeat(column), &content[staff.start()..end]);
+
+        parts.push(Part {
+            instrument,
+            staff: dedent(
+                &EMPTY_WITH.replace(
+                    &INSTRUMENT_NAMES.replace_all(&staff_text, ""),
+                    "",
+                ),
+            ),
+        });
+        last_end = end;
+    }
+
+    parts
+}
+
+fn remove_scores(content: &str) -> String {
+    let mut without_scores = String::new();
+    let mut rest = content;
+
+    while let Some(score) = SCORE.find(rest) {
+        let open = score.end() - 1;
+        let end = get_balanced_end(rest, open, "{", "}");
+
+        without_scores.push_str(&rest[..score.start()]);
+        rest = &rest[end..];
+    }
+
+    without_scores.push_str(rest);
+
+    BLANK_LINES
+        .replace_all(without_scores.trim_end(), "\n\n")
+        .to_string()
+}
+
+/// "Alto Sax. 1 in E♭" as "alto-sax-1-in-e-flat".
+fn get_instrument_file_name(instrument: &str) -> String {
+    instrument
+        .replace('♭', " flat")
+        .replace('♯', " sharp")
+        .to_lowercase()
+        .split(|char: char| !char.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-")
+}
+
+fn get_part_content(main: &str, part: &Part) -> String {
+    let main = update_header(&update_includes(main, "../"), &part.instrument);
+    let staff = indent(&part.staff, 2);
+
+    formatdoc!(
+        "
+        {main}
+
+        \\paper {{
+          indent = 0
+        }}
+
+        \\score {{
+          \\compressMMRests
+        {staff}
+        }}
+        "
+    )
+}
+
+fn get_score_parts(ly_file: &Path) -> Vec<Part> {
+    let directory = ly_file.parent().expect("Failed to get score directory.");
+    let mut files = vec![ly_file.to_path_buf()];
+
+    files.extend(
+        glob(&format!("{}/*.ily", directory.display()))
+            .expect("Failed to read glob pattern")
+            .flatten(),
+    );
+
+    let mut parts: Vec<Part> = vec![];
+
+    for file in files {
+        let Ok(content) = read_to_string(&file) else {
+            continue;
+        };
+
+        for part in get_parts(&content) {
+            if !parts
+                .iter()
+                .any(|existing| existing.instrument == part.instrument)
+            {
+                parts.push(part);
+            }
+        }
+    }
+
+    parts
+}
+
+pub fn main(
+    search_term: &String,
+    search_artist: bool,
+    search_title: bool,
+    scores_directory: &Option<String>,
+    pdfs_directory: &Option<String>,
+) {
+    let Some(ly_file) = get_selected_ly_file(
+        search_term,
+        search_artist,
+        search_title,
+        scores_directory,
+    ) else {
+        println!("No score found matching \"{search_term}\".");
+        return;
+    };
+
+    let ly_file = PathBuf::from(ly_file);
+    let parts = get_score_parts(&ly_file);
+
+    if parts.is_empty() {
+        println!(
+            "No staves with an instrumentName found in {}",
+            ly_file.display()
+        );
+        return;
+    }
+
+    let main = remove_scores(
+        &read_to_string(&ly_file).expect("Failed to read score file."),
+    );
+    let stem = ly_file
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .expect("Failed to get score file name.");
+    let parts_directory = ly_file
+        .parent()
+        .expect("Failed to get score directory.")
+        .join(PARTS_DIRECTORY);
+
+    create_dir_all(&parts_directory)
+        .expect("Failed to create parts directory.");
+
+    for part in parts {
+        let instrument = get_instrument_file_name(&part.instrument);
+        let part_file =
+            parts_directory.join(format!("{stem}-{instrument}.ly"));
+
+        write(&part_file, get_part_content(&main, &part)).unwrap_or_else(
+            |err| panic!("Failed to write {} ({err})", part_file.display()),
+        );
+
+        let _ = compile_input_file(
+            &part_file,
+            &None,
+            scores_directory,
+            pdfs_directory,
+            &CompileOptions {
+                force: true,
+                ..Default::default()
+            },
+        );
+    }
+}
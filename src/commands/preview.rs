@@ -0,0 +1,337 @@
+use std::fs::{create_dir_all, read, remove_file, rename};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread::spawn;
+
+use glob::glob;
+use human_sort::compare;
+use indoc::formatdoc;
+
+use super::compile::run_lilypond;
+use super::get_scores_directory_from_arg;
+use super::scores::{get_selected_ly_file, TEMPORARY_DIRECTORY};
+use super::watch::watch_scores;
+
+pub const DEFAULT_PORT: u16 = 8000;
+
+#[derive(Default)]
+struct Preview {
+    pages: Vec<String>,
+    error: Option<String>,
+    version: usize,
+}
+
+/// A score compiled to svg pages, and the browsers waiting for it to change.
+struct Server {
+    ly_file: PathBuf,
+    lilypond_version: Option<String>,
+    scores_directory: String,
+    output_directory: String,
+    preview: Mutex<Preview>,
+    clients: Mutex<Vec<TcpStream>>,
+    /// Reports only to the browser, e.g. while a terminal editor is open.
+    quiet: bool,
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+impl Server {
+    fn get_svg_files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> =
+            glob(&format!("{}/*.svg", self.output_directory))
+                .expect("Failed to read glob pattern")
+                .flatten()
+                .collect();
+
+        files.sort_by(|a, b| {
+            compare(&a.display().to_string(), &b.display().to_string())
+        });
+
+        files
+    }
+
+    /// Recompiles the score, keeping the previous pages when it fails.
+    fn compile(&self) {
+        let old_files = self.get_svg_files();
+        let temporary_directory = format!("{}/next", self.output_directory);
+
+        create_dir_all(&temporary_directory)
+            .expect("Failed to create preview directory.");
+
+        let result = run_lilypond(
+            &self.ly_file,
+            &self.lilypond_version,
+            &["-dbackend=svg"],
+            &self.scores_directory,
+            &temporary_directory,
+        );
+
+        let mut preview =
+            self.preview.lock().expect("Failed to read preview.");
+
+        match result {
+            Ok(_) => {
+                for file in old_files {
+                    let _ = remove_file(file);
+                }
+
+                let mut pages = vec![];
+
+                for file in glob(&format!("{temporary_directory}/*.svg"))
+                    .expect("Failed to read glob pattern")
+                    .flatten()
+                {
+                    if let Some(name) = file.file_name() {
+                        let destination =
+                            Path::new(&self.output_directory).join(name);
+
+                        if rename(&file, destination).is_ok() {
+                            pages.push(name.to_string_lossy().to_string());
+                        }
+                    }
+                }
+
+                pages.sort_by(|a, b| compare(a, b));
+                preview.pages = pages;
+                preview.error = None;
+
+                if !self.quiet {
+                    println!("Compiled {}", self.ly_file.display());
+                }
+            }
+            Err(output) => {
+                if !self.quiet {
+                    print!("{output}");
+                }
+
+                preview.error = Some(output);
+            }
+        }
+
+        preview.version += 1;
+    }
+
+    fn notify_clients(&self) {
+        let mut clients = self
+            .clients
+            .lock()
+            .expect("Failed to read preview clients.");
+
+        clients.retain_mut(|client| {
+            client.write_all(b"data: reload\n\n").is_ok()
+        });
+    }
+
+    fn get_page(&self) -> String {
+        let preview = self.preview.lock().expect("Failed to read preview.");
+        let version = preview.version;
+        let title = escape_html(
+            &self
+                .ly_file
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy(),
+        );
+        let pages = preview
+            .pages
+            .iter()
+            .map(|page| format!("<img src=\"/pages/{page}?v={version}\">"))
+            .collect::<Vec<String>>()
+            .join("\n");
+        let overlay =
+            preview.error.as_ref().map_or_else(String::new, |error| {
+                format!("<pre class=\"error\">{}</pre>", escape_html(error))
+            });
+
+        formatdoc!(
+            r#"
+            <!DOCTYPE html>
+            <html>
+            <head>
+            <meta charset="utf-8">
+            <title>{title}</title>
+            <style>
+              body {{ background: #ddd; margin: 0; }}
+              img {{ background: white; display: block; margin: 1em auto; max-width: 100%; }}
+              .error {{ background: rgba(40, 0, 0, 0.9); color: #fcc; inset: 0; margin: 0; overflow: auto; padding: 2em; position: fixed; white-space: pre-wrap; }}
+            </style>
+            </head>
+            <body>
+            {pages}
+            {overlay}
+            <script>
+              new EventSource("/events").onmessage = () => location.reload();
+            </script>
+            </body>
+            </html>
+            "#
+        )
+    }
+
+    fn handle(&self, mut stream: TcpStream) {
+        let mut request_line = String::new();
+        let mut reader = BufReader::new(
+            stream.try_clone().expect("Failed to read request."),
+        );
+
+        if reader.read_line(&mut request_line).is_err() {
+            return;
+        }
+
+        let mut line = String::new();
+
+        while reader.read_line(&mut line).is_ok_and(|length| length > 2) {
+            line.clear();
+        }
+
+        let path = request_line
+            .split_whitespace()
+            .nth(1)
+            .unwrap_or("/")
+            .split('?')
+            .next()
+            .unwrap_or("/");
+
+        if path == "/events" {
+            if stream
+                .write_all(
+                    b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n\r\n",
+                )
+                .is_ok()
+            {
+                self.clients
+                    .lock()
+                    .expect("Failed to read preview clients.")
+                    .push(stream);
+            }
+
+            return;
+        }
+
+        let (status, content_type, body) = if path == "/" {
+            (
+                "200 OK",
+                "text/html; charset=utf-8",
+                self.get_page().into_bytes(),
+            )
+        } else if let Some(body) = path
+            .strip_prefix("/pages/")
+            .filter(|page| !page.contains('/') && !page.contains(".."))
+            .and_then(|page| {
+                read(Path::new(&self.output_directory).join(page)).ok()
+            })
+        {
+            ("200 OK", "image/svg+xml", body)
+        } else {
+            ("404 Not Found", "text/plain", b"Not found".to_vec())
+        };
+
+        let header = format!(
+            "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n",
+            body.len()
+        );
+
+        let _ = stream
+            .write_all(header.as_bytes())
+            .and_then(|()| stream.write_all(&body));
+    }
+}
+
+fn serve(server: &Arc<Server>, listener: &TcpListener) {
+    for stream in listener.incoming().flatten() {
+        let server = Arc::clone(server);
+
+        spawn(move || server.handle(stream));
+    }
+}
+
+pub fn print_preview_url(ly_file: &Path, port: u16) {
+    println!(
+        "Previewing {} at http://localhost:{port}",
+        ly_file.display()
+    );
+}
+
+/// Serves `ly_file` on `port`, recompiling and reloading the page whenever
+/// it or its includes change.
+pub fn serve_score(
+    ly_file: PathBuf,
+    port: u16,
+    lilypond_version: &Option<String>,
+    scores_directory: String,
+    quiet: bool,
+) {
+    let stem = ly_file
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .expect("Failed to get score file name.");
+
+    let server = Arc::new(Server {
+        ly_file: ly_file.clone(),
+        lilypond_version: lilypond_version.clone(),
+        scores_directory: scores_directory.clone(),
+        output_directory: format!("{TEMPORARY_DIRECTORY}/serve/{stem}"),
+        preview: Mutex::new(Preview::default()),
+        clients: Mutex::new(vec![]),
+        quiet,
+    });
+
+    let listener = match TcpListener::bind(("127.0.0.1", port)) {
+        Ok(listener) => listener,
+        Err(err) => {
+            println!("Failed to listen on port {port} ({err})");
+            return;
+        }
+    };
+
+    server.compile();
+
+    {
+        let server = Arc::clone(&server);
+
+        spawn(move || serve(&server, &listener));
+    }
+
+    if !quiet {
+        print_preview_url(&ly_file, port);
+    }
+
+    watch_scores(vec![ly_file], scores_directory, move |_| {
+        server.compile();
+        server.notify_clients();
+    })
+    .expect("Failed to watch score.");
+}
+
+pub fn main(
+    search_term: &String,
+    search_artist: bool,
+    search_title: bool,
+    port: &Option<u16>,
+    lilypond_version: &Option<String>,
+    scores_directory: &Option<String>,
+) {
+    let Some(ly_file) = get_selected_ly_file(
+        search_term,
+        search_artist,
+        search_title,
+        scores_directory,
+    ) else {
+        println!("No score found matching \"{search_term}\".");
+        return;
+    };
+
+    serve_score(
+        PathBuf::from(ly_file),
+        port.unwrap_or(DEFAULT_PORT),
+        lilypond_version,
+        get_scores_directory_from_arg(scores_directory),
+        false,
+    );
+}
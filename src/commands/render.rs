@@ -0,0 +1,252 @@
+use std::env::var;
+use std::fs::{create_dir_all, read, remove_dir_all, write};
+use std::io::{self, stdin, stdout, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use clap::ValueEnum;
+use indoc::formatdoc;
+
+use super::compile::run_lilypond;
+use super::get_scores_directory_from_arg;
+use super::helpers::write_helper_files;
+use super::lilypond::resolve_lilypond_version;
+use super::scores::TEMPORARY_DIRECTORY;
+
+#[derive(Clone, Debug, ValueEnum)]
+pub enum GraphicsProtocol {
+    Kitty,
+    Iterm,
+    Sixel,
+}
+
+const KITTY_CHUNK_SIZE: usize = 4096;
+
+const RESOLUTION: usize = 150;
+
+fn detect_protocol() -> GraphicsProtocol {
+    let term = var("TERM").unwrap_or_default();
+    let term_program = var("TERM_PROGRAM").unwrap_or_default();
+
+    if term.contains("kitty")
+        || var("KITTY_WINDOW_ID").is_ok()
+        || term_program == "ghostty"
+    {
+        GraphicsProtocol::Kitty
+    } else if ["iTerm.app", "WezTerm"].contains(&term_program.as_str()) {
+        GraphicsProtocol::Iterm
+    } else {
+        GraphicsProtocol::Sixel
+    }
+}
+
+fn get_image_sequence(
+    png: &[u8],
+    protocol: &GraphicsProtocol,
+) -> io::Result<Vec<u8>> {
+    match protocol {
+        GraphicsProtocol::Kitty => {
+            let encoded = STANDARD.encode(png);
+            let chunks: Vec<&[u8]> =
+                encoded.as_bytes().chunks(KITTY_CHUNK_SIZE).collect();
+            let mut sequence = vec![];
+
+            for (index, chunk) in chunks.iter().enumerate() {
+                let more = u8::from(index + 1 < chunks.len());
+                let control = if index == 0 {
+                    format!("f=100,a=T,m={more}")
+                } else {
+                    format!("m={more}")
+                };
+
+                sequence.extend(format!("\x1b_G{control};").as_bytes());
+                sequence.extend(*chunk);
+                sequence.extend(b"\x1b\\");
+            }
+
+            Ok(sequence)
+        }
+        GraphicsProtocol::Iterm => Ok(format!(
+            "\x1b]1337;File=inline=1;size={};preserveAspectRatio=1:{}\x07",
+            png.len(),
+            STANDARD.encode(png)
+        )
+        .into_bytes()),
+        GraphicsProtocol::Sixel => {
+            let mut command = Command::new("img2sixel")
+                .stdin(std::process::Stdio::piped())
+                .stdout(std::process::Stdio::piped())
+                .spawn()
+                .map_err(|err| {
+                    io::Error::new(err.kind(), "img2sixel is not installed")
+                })?;
+
+            command
+                .stdin
+                .take()
+                .expect("Failed to open img2sixel input.")
+                .write_all(png)?;
+
+            Ok(command.wait_with_output()?.stdout)
+        }
+    }
+}
+
+/// Draws `png` inline in the terminal.
+pub fn display_image(png: &Path, protocol: &Option<GraphicsProtocol>) {
+    let protocol = protocol.clone().unwrap_or_else(detect_protocol);
+
+    let result = read(png)
+        .and_then(|png| get_image_sequence(&png, &protocol))
+        .and_then(|sequence| {
+            let mut stdout = stdout();
+
+            stdout.write_all(&sequence)?;
+            stdout.write_all(b"\n")?;
+            stdout.flush()
+        });
+
+    if let Err(err) = result {
+        println!("Failed to display {} ({err})", png.display());
+    }
+}
+
+/// The directory lilypond should search for `helpers/settings.ily`, writing
+/// the built-in helpers to `directory` when the scores directory has none.
+fn get_include_directory(
+    scores_directory: &Option<String>,
+    directory: &str,
+) -> String {
+    let scores_directory = get_scores_directory_from_arg(scores_directory);
+
+    if Path::new(&format!("{scores_directory}/helpers/settings.ily")).exists()
+    {
+        scores_directory
+    } else {
+        write_helper_files(directory).expect("Failed to write helper files.");
+        directory.to_string()
+    }
+}
+
+/// The png lilypond wrote for `stem` in `directory`: cropped, single page,
+/// or the first of several pages.
+fn find_png(directory: &str, stem: &str) -> Option<PathBuf> {
+    [
+        format!("{directory}/{stem}.cropped.png"),
+        format!("{directory}/{stem}.png"),
+        format!("{directory}/{stem}-page1.png"),
+    ]
+    .into_iter()
+    .map(PathBuf::from)
+    .find(|png| png.exists())
+}
+
+/// Compiles `ly_file` to png in a clean `directory`, returning the first page.
+pub fn render_png(
+    ly_file: &Path,
+    directory: &str,
+    arguments: &[&str],
+    lilypond_version: &Option<String>,
+    scores_directory: &Option<String>,
+) -> Option<PathBuf> {
+    let stem = ly_file
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .expect("Failed to get file name.");
+    let include_directory = get_include_directory(scores_directory, directory);
+    let resolution = format!("-dresolution={RESOLUTION}");
+    let mut arguments = arguments.to_vec();
+
+    arguments.extend(["--png", &resolution]);
+
+    if let Err(output) = run_lilypond(
+        ly_file,
+        lilypond_version,
+        &arguments,
+        &include_directory,
+        directory,
+    ) {
+        print!("{output}");
+        return None;
+    }
+
+    find_png(directory, stem)
+}
+
+fn get_fragment(fragment: &Option<String>) -> Option<String> {
+    if let Some(fragment) = fragment {
+        return Some(fragment.to_string());
+    }
+
+    if stdin().is_terminal() {
+        return None;
+    }
+
+    let mut fragment = String::new();
+
+    stdin()
+        .read_to_string(&mut fragment)
+        .expect("Failed to read fragment from stdin.");
+
+    Some(fragment)
+}
+
+pub fn main(
+    fragment: &Option<String>,
+    protocol: &Option<GraphicsProtocol>,
+    lilypond_version: &Option<String>,
+    scores_directory: &Option<String>,
+) {
+    let Some(fragment) =
+        get_fragment(fragment).filter(|fragment| !fragment.trim().is_empty())
+    else {
+        println!(
+            "Please provide a lilypond fragment as an argument or on stdin."
+        );
+        return;
+    };
+
+    let Some(version) = resolve_lilypond_version(lilypond_version) else {
+        println!("Failed to find a lilypond version. Install one with `thoth lilypond install` or use --lilypond-version.");
+        return;
+    };
+
+    let directory = format!("{TEMPORARY_DIRECTORY}/render/fragment");
+    let _ = remove_dir_all(&directory);
+    create_dir_all(&directory).expect("Failed to create render directory.");
+
+    let ly_file = PathBuf::from(format!("{directory}/fragment.ly"));
+    let fragment = fragment.trim();
+
+    write(
+        &ly_file,
+        formatdoc!(
+            "
+            \\version \"{version}\"
+
+            \\include \"helpers/settings.ily\"
+
+            \\header {{
+              tagline = ##f
+            }}
+
+            {{
+              {fragment}
+            }}
+            "
+        ),
+    )
+    .expect("Failed to write fragment file.");
+
+    if let Some(png) = render_png(
+        &ly_file,
+        &directory,
+        &["-dcrop"],
+        lilypond_version,
+        scores_directory,
+    ) {
+        display_image(&png, protocol);
+    }
+}
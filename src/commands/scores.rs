@@ -2,19 +2,37 @@ use std::fs::read_dir;
 use std::fs::DirEntry;
 use std::io::ErrorKind;
 use std::io::{Cursor, Error};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::id;
 
+use chrono::offset::Local;
 use glob::glob;
 use skim::prelude::*;
 
-use crate::commands::compile::is_compiled;
+use crate::commands::compile::{is_compiled, FileFormat};
 use crate::commands::get_pdfs_directory_from_arg;
 use crate::commands::get_scores_directory_from_arg;
+use crate::commands::parts::PARTS_DIRECTORY;
 
 pub const TEMPORARY_DIRECTORY: &str = "/tmp/thoth";
 
-pub fn get_temporary_ly_file() -> String {
-    format!("{TEMPORARY_DIRECTORY}/sketch.ly")
+pub fn get_sketches_directory() -> String {
+    format!("{TEMPORARY_DIRECTORY}/sketches")
+}
+
+/// Timestamp for sketch directory names, without colons so that paths stay
+/// portable.
+pub const SKETCH_TIME_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+
+/// A directory for a new sketch, unique to this session so that concurrent
+/// sketches don't overwrite each other.
+pub fn get_new_sketch_directory() -> String {
+    format!(
+        "{}/{}-{}",
+        get_sketches_directory(),
+        Local::now().format(SKETCH_TIME_FORMAT),
+        id()
+    )
 }
 
 fn get_items(paths: &[PathBuf]) -> Receiver<Arc<dyn SkimItem>> {
@@ -87,7 +105,9 @@ pub fn search(
                     let score_file =
                         entry.unwrap_or_else(|err| panic!("{err}"));
 
-                    if score_file.file_name() == ".DS_Store" {
+                    if score_file.file_name() == ".DS_Store"
+                        || score_file.file_name() == "sketches"
+                    {
                         continue;
                     }
 
@@ -137,6 +157,34 @@ pub fn get_score_ly_file(score: &String) -> Option<String> {
         })
 }
 
+/// The main file of the score matching `search_term`, prompting for a
+/// selection when several scores match.
+pub fn get_selected_ly_file(
+    search_term: &str,
+    search_artist: bool,
+    search_title: bool,
+    scores_directory: &Option<String>,
+) -> Option<String> {
+    let matching_scores = search(
+        &vec![search_term.to_string()],
+        search_artist,
+        search_title,
+        scores_directory,
+    );
+
+    let score = if matching_scores.len() > 1 {
+        get_selected_items(&matching_scores, false)
+            .ok()?
+            .first()?
+            .output()
+            .to_string()
+    } else {
+        matching_scores.first()?.to_str()?.to_string()
+    };
+
+    get_score_ly_file(&score)
+}
+
 pub fn get_found_ly_files(
     search_terms: &Vec<String>,
     search_artist: bool,
@@ -160,58 +208,70 @@ pub fn get_found_ly_files(
         .collect()
 }
 
-pub fn get_found_pdfs(
+/// The part files written by `thoth parts`, each of which compiles to
+/// outputs named after its own stem.
+pub fn get_part_files(score: &Path) -> Vec<PathBuf> {
+    let pattern = format!("{}/{PARTS_DIRECTORY}/*.ly", score.display());
+
+    glob(&pattern)
+        .expect("Failed to read glob pattern")
+        .flatten()
+        .collect()
+}
+
+/// The score's `.ly` files followed by its part files.
+pub fn get_input_files(score: &Path) -> Vec<PathBuf> {
+    let pattern = format!("{}/*.ly", score.display());
+
+    glob(&pattern)
+        .expect("Failed to read glob pattern")
+        .flatten()
+        .chain(get_part_files(score))
+        .collect()
+}
+
+pub fn get_found_outputs(
     search_terms: &Vec<String>,
     search_artist: bool,
     search_title: bool,
     scores_directory: &Option<String>,
     pdfs_directory: &Option<String>,
+    format: FileFormat,
 ) -> Vec<PathBuf> {
-    let pdfs_directory = &get_pdfs_directory_from_arg(pdfs_directory);
+    let pdfs_directory = get_pdfs_directory_from_arg(pdfs_directory);
 
     let matching_scores =
         search(search_terms, search_artist, search_title, scores_directory);
 
-    let mut matching_pdfs = vec![];
+    let mut matching_outputs = vec![];
 
     for score in matching_scores {
-        let pattern = format!("{}/*.ly", score.display());
-
-        for input_file in glob(&pattern)
-            .expect("Failed to read glob pattern")
-            .flatten()
-        {
-            let output_file_pattern = format!(
-                "{pdfs_directory}/*{}*.pdf",
-                input_file
-                    .file_stem()
-                    .unwrap_or_else(|| panic!(
-                        "{}",
-                        format!(
-                            "Failed to get file stem for file: {}",
-                            &input_file.display()
-                        )
-                    ))
-                    .to_str()
-                    .unwrap_or_else(|| panic!(
-                        "{}",
-                        format!(
-                            "Failed to get file stem for file: {}",
-                            &input_file.display()
-                        )
-                    ))
-            );
-
-            for entry in glob(&output_file_pattern)
-                .expect("Failed to read glob pattern")
-                .flatten()
+        for input_file in get_input_files(&score) {
+            for entry in format.get_output_files(&input_file, &pdfs_directory)
             {
                 if is_compiled(&input_file, &entry) {
-                    matching_pdfs.push(entry);
+                    matching_outputs.push(entry);
                 }
             }
         }
     }
 
-    matching_pdfs
+    matching_outputs
+}
+
+pub fn get_found_pdfs(
+    search_terms: &Vec<String>,
+    search_artist: bool,
+    search_title: bool,
+    scores_directory: &Option<String>,
+    pdfs_directory: &Option<String>,
+) -> Vec<PathBuf> {
+    get_found_outputs(
+        search_terms,
+        search_artist,
+        search_title,
+        scores_directory,
+        pdfs_directory,
+        FileFormat::Pdf,
+    )
 }
@@ -1,12 +1,458 @@
-use super::create::{self, ScoreFileSettings};
+use std::fs::{copy, create_dir_all, read_to_string, remove_dir_all, write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
-pub fn main(lilypond_version: &Option<String>) {
-    create::main(
-        &ScoreFileSettings::default(),
-        true,
-        true,
+use chrono::offset::Local;
+use glob::glob;
+use once_cell::sync::Lazy;
+use owo_colors::OwoColorize;
+use regex::{Captures, Regex};
+
+use super::create::{
+    self, create_score, get_file_system_name, CreateOptions, ScoreFileSettings,
+};
+use super::edit::{edit_file, EditOptions};
+use super::get_scores_directory_from_arg;
+use super::parts::get_expression_end;
+use super::scores::{
+    get_selected_items, get_sketches_directory, SKETCH_TIME_FORMAT,
+    TEMPORARY_DIRECTORY,
+};
+use super::table;
+use super::templates::Template;
+use super::SketchCommand;
+use crate::config::Config;
+
+static TITLE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?m)^(\s*title\s*=\s*)"([^"]*)""#)
+        .expect("Failed to create title regex.")
+});
+
+static COMPOSER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?m)^\s*composer\s*=\s*"([^"]*)""#)
+        .expect("Failed to create composer regex.")
+});
+
+static VARIABLE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^([A-Za-z][\w-]*)\s*=\s*")
+        .expect("Failed to create variable regex.")
+});
+
+fn get_files(pattern: &str) -> Vec<PathBuf> {
+    glob(pattern)
+        .expect("Failed to read glob pattern")
+        .flatten()
+        .collect()
+}
+
+/// The .ly and .ily files of the sketch in `directory`, main file first.
+fn get_sketch_files(directory: &Path) -> Vec<PathBuf> {
+    let directory = directory.display();
+    let mut files = get_files(&format!("{directory}/*.ly"));
+
+    files.extend(get_files(&format!("{directory}/*.ily")));
+
+    files
+}
+
+fn get_main_content(files: &[PathBuf]) -> String {
+    files
+        .first()
+        .and_then(|file| read_to_string(file).ok())
+        .unwrap_or_default()
+}
+
+fn get_title(content: &str) -> String {
+    TITLE.captures(content).map_or_else(
+        || "Sketch".to_string(),
+        |captures| captures[2].to_string(),
+    )
+}
+
+fn get_composer(content: &str) -> String {
+    COMPOSER
+        .captures(content)
+        .map_or_else(Config::get_composer, |captures| captures[1].to_string())
+}
+
+pub fn is_modified_since(directory: &Path, time: SystemTime) -> bool {
+    get_sketch_files(directory).iter().any(|file| {
+        file.metadata()
+            .and_then(|metadata| metadata.modified())
+            .is_ok_and(|modified| modified > time)
+    })
+}
+
+/// Copies the sketch in `directory` to
+/// `scores/<composer>/sketches/<timestamp>-<title>`.
+pub fn save_sketch(directory: &Path) -> Option<PathBuf> {
+    let files = get_sketch_files(directory);
+
+    if files.is_empty() {
+        println!("No sketch to save.");
+        return None;
+    }
+
+    let content = get_main_content(&files);
+    let saved_directory = PathBuf::from(format!(
+        "{}/scores/{}/sketches/{}-{}",
+        Config::get_scores_directory(),
+        get_file_system_name(&get_composer(&content)),
+        Local::now().format(SKETCH_TIME_FORMAT),
+        get_file_system_name(&get_title(&content))
+    ));
+
+    create_dir_all(&saved_directory)
+        .expect("Failed to create sketches directory.");
+
+    for file in &files {
+        if let Some(name) = file.file_name() {
+            if let Err(err) = copy(file, saved_directory.join(name)) {
+                println!("Failed to save {} ({err})", file.display());
+            }
+        }
+    }
+
+    println!("Saved sketch to {}", saved_directory.display());
+
+    Some(saved_directory)
+}
+
+/// The directories of the sketches being edited, oldest first.
+fn get_sketch_sessions() -> Vec<PathBuf> {
+    get_files(&format!("{}/*", get_sketches_directory()))
+        .into_iter()
+        .filter(|session| session.is_dir())
+        .collect()
+}
+
+fn select_sketch(sketches: Vec<PathBuf>) -> Option<PathBuf> {
+    if sketches.len() > 1 {
+        get_selected_items(&sketches, false)
+            .ok()?
+            .first()
+            .map(|item| PathBuf::from(item.output().to_string()))
+    } else {
+        sketches.into_iter().next()
+    }
+}
+
+fn get_saved_sketches(scores_directory: &str) -> Vec<PathBuf> {
+    get_files(&format!("{scores_directory}/scores/*/sketches/*"))
+        .into_iter()
+        .filter(|sketch| sketch.is_dir())
+        .collect()
+}
+
+/// "composer/sketch" for a saved sketch directory.
+fn get_sketch_name(sketch: &Path) -> String {
+    let name = |path: Option<&Path>| {
+        path.and_then(Path::file_name)
+            .and_then(|name| name.to_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    let composer = name(sketch.parent().and_then(Path::parent));
+
+    format!("{composer}/{}", name(Some(sketch)))
+}
+
+fn find_sketch(search_term: &str, scores_directory: &str) -> Option<PathBuf> {
+    select_sketch(
+        get_saved_sketches(scores_directory)
+            .into_iter()
+            .filter(|sketch| {
+                let name = get_sketch_name(sketch);
+
+                name.contains(search_term)
+                    || name.replace('-', " ").contains(search_term)
+            })
+            .collect(),
+    )
+}
+
+fn list_sketches(scores_directory: &str) {
+    let titles = vec![
+        "Composer".italic().to_string(),
+        "Sketch".italic().to_string(),
+    ];
+
+    let rows: Vec<Vec<String>> = get_saved_sketches(scores_directory)
+        .iter()
+        .map(|sketch| {
+            let name = get_sketch_name(sketch);
+            let (composer, sketch) = name.split_once('/').unwrap_or_default();
+
+            vec![composer.yellow().to_string(), sketch.to_string()]
+        })
+        .collect();
+
+    table::print(&titles, rows, &None);
+}
+
+fn open_sketch(
+    sketch: &Path,
+    is_session: bool,
+    lilypond_version: &Option<String>,
+) {
+    let Some(ly_file) = get_sketch_files(sketch).into_iter().next() else {
+        println!("No lilypond file found in {}", sketch.display());
+        return;
+    };
+
+    edit_file(
+        &ly_file.display().to_string(),
+        &EditOptions {
+            is_sketch: is_session,
+            ..EditOptions::default()
+        },
         lilypond_version,
         &None,
-        &None,
+        &Some(TEMPORARY_DIRECTORY.to_string()),
     );
 }
+
+/// Reopens the most recent sketch: one still being edited, or else the
+/// last one saved.
+fn open_last_sketch(
+    scores_directory: &str,
+    lilypond_version: &Option<String>,
+) {
+    if let Some(session) = get_sketch_sessions().pop() {
+        open_sketch(&session, true, lilypond_version);
+    } else if let Some(sketch) = get_saved_sketches(scores_directory)
+        .into_iter()
+        .max_by_key(|sketch| sketch.file_name().map(ToOwned::to_owned))
+    {
+        open_sketch(&sketch, false, lilypond_version);
+    } else {
+        println!("No sketches found.");
+    }
+}
+
+/// The top-level variable assignments in `content`, as (name, value).
+fn get_variables(content: &str) -> Vec<(String, String)> {
+    VARIABLE
+        .captures_iter(content)
+        .filter_map(|captures| {
+            let assignment = captures.get(0)?;
+            let end = get_expression_end(content, assignment.end());
+
+            Some((
+                captures[1].to_string(),
+                content[assignment.end()..end].to_string(),
+            ))
+        })
+        .collect()
+}
+
+/// Replaces the values of the variables in `content` that the sketch also
+/// defines, carrying the sketch's music over into a new template.
+fn replace_variables(content: &str, variables: &[(String, String)]) -> String {
+    let mut replaced = String::new();
+    let mut position = 0;
+
+    for captures in VARIABLE.captures_iter(content) {
+        let Some(assignment) = captures.get(0) else {
+            continue;
+        };
+
+        if assignment.start() < position {
+            continue;
+        }
+
+        let Some((_, value)) =
+            variables.iter().find(|(name, _)| *name == captures[1])
+        else {
+            continue;
+        };
+
+        replaced.push_str(&content[position..assignment.end()]);
+        replaced.push_str(value);
+        position = get_expression_end(content, assignment.end());
+    }
+
+    replaced.push_str(&content[position..]);
+
+    replaced
+}
+
+fn set_title(content: &str, title: &str) -> String {
+    TITLE
+        .replace(content, |captures: &Captures| {
+            format!("{}\"{title}\"", &captures[1])
+        })
+        .to_string()
+}
+
+/// Copies the sketch files into `score_directory`, renaming them (and their
+/// includes) after the new title.
+fn copy_sketch(
+    files: &[PathBuf],
+    score_directory: &Path,
+    title: &str,
+) -> Vec<String> {
+    let file_system_title = get_file_system_name(title);
+    let stem = files
+        .first()
+        .and_then(|file| file.file_stem())
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default()
+        .to_string();
+
+    create_dir_all(score_directory)
+        .expect("Failed to create score directory.");
+
+    files
+        .iter()
+        .filter_map(|file| {
+            let name = file.file_name()?.to_str()?;
+            let name = name.strip_prefix(&stem).map_or_else(
+                || name.to_string(),
+                |rest| format!("{file_system_title}{rest}"),
+            );
+            let content = set_title(&read_to_string(file).ok()?, title)
+                .replace(
+                    &format!("\\include \"{stem}-"),
+                    &format!("\\include \"{file_system_title}-"),
+                );
+            let destination = score_directory.join(name);
+
+            write(&destination, content).ok()?;
+
+            Some(destination.display().to_string())
+        })
+        .collect()
+}
+
+fn promote_sketch(
+    sketch: &Path,
+    title: &Option<String>,
+    template: &Option<Template>,
+    lilypond_version: &Option<String>,
+    scores_directory: &str,
+) {
+    let files = get_sketch_files(sketch);
+    let content = get_main_content(&files);
+    let title = title.clone().unwrap_or_else(|| get_title(&content));
+    let composer = get_composer(&content);
+    let score_directory = PathBuf::from(format!(
+        "{scores_directory}/scores/{}/{}",
+        get_file_system_name(&composer),
+        get_file_system_name(&title)
+    ));
+
+    if score_directory.exists() {
+        println!("{} already exists.", score_directory.display());
+        return;
+    }
+
+    let created_files = if template.is_some() {
+        let settings = ScoreFileSettings {
+            title: title.clone(),
+            composer: Some(composer),
+            template: template.clone(),
+            ..ScoreFileSettings::default()
+        };
+
+        let created_files = match create_score(
+            &settings,
+            lilypond_version,
+            &Some(scores_directory.to_string()),
+            &CreateOptions::default(),
+        ) {
+            Ok(files) => files,
+            Err(message) => {
+                println!("{message}");
+                return;
+            }
+        };
+
+        let variables: Vec<(String, String)> = files
+            .iter()
+            .filter_map(|file| read_to_string(file).ok())
+            .flat_map(|content| get_variables(&content))
+            .collect();
+
+        for file in &created_files {
+            if let Ok(content) = read_to_string(file) {
+                let _ = write(file, replace_variables(&content, &variables));
+            }
+        }
+
+        created_files
+    } else {
+        copy_sketch(&files, &score_directory, &title)
+    };
+
+    if let Err(err) = remove_dir_all(sketch) {
+        println!("Failed to remove {} ({err})", sketch.display());
+    }
+
+    println!("Promoted {} to \"{title}\":", get_sketch_name(sketch));
+
+    for file in created_files {
+        println!("{file}");
+    }
+}
+
+pub fn main(
+    command: &Option<SketchCommand>,
+    template: &Option<Template>,
+    open_last: bool,
+    lilypond_version: &Option<String>,
+) {
+    let scores_directory = get_scores_directory_from_arg(&None);
+
+    match command {
+        Some(SketchCommand::Save) => {
+            match select_sketch(get_sketch_sessions()) {
+                Some(session) => {
+                    save_sketch(&session);
+                }
+                None => println!("No sketch to save."),
+            }
+        }
+        Some(SketchCommand::List) => list_sketches(&scores_directory),
+        Some(SketchCommand::Open { sketch }) => {
+            match find_sketch(sketch, &scores_directory) {
+                Some(sketch) => open_sketch(&sketch, false, lilypond_version),
+                None => println!("No sketch found matching \"{sketch}\"."),
+            }
+        }
+        Some(SketchCommand::Promote {
+            sketch,
+            title,
+            template,
+        }) => match find_sketch(sketch, &scores_directory) {
+            Some(sketch) => promote_sketch(
+                &sketch,
+                title,
+                template,
+                lilypond_version,
+                &scores_directory,
+            ),
+            None => println!("No sketch found matching \"{sketch}\"."),
+        },
+        None if open_last => {
+            open_last_sketch(&scores_directory, lilypond_version);
+        }
+        None => create::main(
+            &ScoreFileSettings {
+                template: template
+                    .clone()
+                    .or_else(|| ScoreFileSettings::default().template),
+                ..ScoreFileSettings::default()
+            },
+            &CreateOptions {
+                edit: true,
+                is_sketch: true,
+                ..CreateOptions::default()
+            },
+            lilypond_version,
+            &None,
+            &None,
+        ),
+    }
+}
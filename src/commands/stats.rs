@@ -0,0 +1,230 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use glob::glob;
+use owo_colors::OwoColorize;
+use regex::Regex;
+use titlecase::titlecase;
+
+use super::compile::FileFormat;
+use super::get_pdfs_directory_from_arg;
+use super::info::get_composition_metadata;
+use super::lilypond::global::get_global_version;
+use super::scores::{get_score_ly_file, search};
+use super::table;
+use super::update_version::is_outdated;
+
+#[derive(Default)]
+struct Counter {
+    counts: HashMap<String, usize>,
+}
+
+impl Counter {
+    fn add(&mut self, value: &str) {
+        *self.counts.entry(value.to_string()).or_default() += 1;
+    }
+
+    fn get_rows(&self) -> Vec<Vec<String>> {
+        let mut counts: Vec<(&String, &usize)> = self.counts.iter().collect();
+
+        counts.sort_by(|(a_value, a_count), (b_value, b_count)| {
+            b_count.cmp(a_count).then(a_value.cmp(b_value))
+        });
+
+        counts
+            .iter()
+            .map(|(value, count)| {
+                vec![value.yellow().to_string(), count.to_string()]
+            })
+            .collect()
+    }
+
+    fn print(&self, title: &str) {
+        if self.counts.is_empty() {
+            return;
+        }
+
+        let titles =
+            vec![title.italic().to_string(), "Scores".italic().to_string()];
+
+        println!();
+        table::print(&titles, self.get_rows(), &None);
+    }
+}
+
+#[derive(Default)]
+struct Statistics {
+    scores: usize,
+    compiled: usize,
+    behind_global: usize,
+    artists: Counter,
+    templates: Counter,
+    versions: Counter,
+    helpers: Counter,
+    keys: Counter,
+    times: Counter,
+}
+
+fn get_score_files(score: &Path, extension: &str) -> Vec<PathBuf> {
+    glob(&format!("{}/*.{extension}", score.display()))
+        .expect("Failed to read glob pattern")
+        .flatten()
+        .collect()
+}
+
+/// A best guess at the template a score was created from, based on the part
+/// files it generates, since scores don't record their template. Custom and
+/// hand-edited scores are reported as the closest built-in template.
+fn get_template_name(score: &Path, ly_file: &str) -> String {
+    let ily_files: Vec<String> = get_score_files(score, "ily")
+        .iter()
+        .map(|file| file.display().to_string())
+        .collect();
+    let has_part =
+        |part: &str| ily_files.iter().any(|file| file.ends_with(part));
+
+    if has_part("saxophones.ily") {
+        "BigBand".to_string()
+    } else if has_part("drums.ily") {
+        "RhythmSection".to_string()
+    } else if has_part("soprano.ily") {
+        "Choir".to_string()
+    } else if has_part("violin-one.ily") {
+        "StringQuartet".to_string()
+    } else if has_part("structure.ily") {
+        if has_part("lyrics.ily") {
+            "Lead".to_string()
+        } else {
+            "Form".to_string()
+        }
+    } else if read_to_string(ly_file)
+        .unwrap_or_default()
+        .contains("PianoStaff")
+    {
+        "Piano".to_string()
+    } else {
+        "Single".to_string()
+    }
+}
+
+fn get_helpers(score: &Path) -> HashSet<String> {
+    let pattern = Regex::new(r#"\\include\s+"helpers/([^"]+)\.ily""#)
+        .expect("Failed to create helper include regex.");
+    let mut helpers = HashSet::new();
+
+    for file in get_score_files(score, "ly")
+        .iter()
+        .chain(get_score_files(score, "ily").iter())
+    {
+        let content = read_to_string(file).unwrap_or_default();
+
+        for captures in pattern.captures_iter(&content) {
+            helpers.insert(captures[1].to_string());
+        }
+    }
+
+    helpers
+}
+
+fn get_artist(score: &Path) -> String {
+    let err = "Failed to get score artist.";
+
+    titlecase(
+        &score
+            .parent()
+            .expect(err)
+            .file_name()
+            .expect(err)
+            .to_str()
+            .expect(err)
+            .replace('-', " "),
+    )
+}
+
+fn print_summary(statistics: &Statistics, global_version: &str) {
+    let style_row = |key: &str, value: usize| {
+        vec![key.yellow().to_string(), value.bold().to_string()]
+    };
+
+    let rows = vec![
+        style_row("scores", statistics.scores),
+        style_row("compiled", statistics.compiled),
+        style_row("outdated", statistics.scores - statistics.compiled),
+        style_row(
+            &format!("behind global ({global_version})"),
+            statistics.behind_global,
+        ),
+    ];
+
+    table::print(&[], rows, &None);
+}
+
+pub fn main(
+    search_terms: &Vec<String>,
+    search_artist: bool,
+    search_title: bool,
+    scores_directory: &Option<String>,
+    pdfs_directory: &Option<String>,
+) {
+    let pdfs_directory = get_pdfs_directory_from_arg(pdfs_directory);
+    let global_version = get_global_version();
+    let mut statistics = Statistics::default();
+
+    for score in
+        search(search_terms, search_artist, search_title, scores_directory)
+    {
+        let Some(ly_file) = get_score_ly_file(
+            &score.to_str().expect("Failed to parse score.").to_string(),
+        ) else {
+            continue;
+        };
+
+        let metadata = get_composition_metadata(&ly_file);
+
+        statistics.scores += 1;
+        statistics.artists.add(&get_artist(&score));
+        statistics
+            .templates
+            .add(&get_template_name(&score, &ly_file));
+
+        if FileFormat::Pdf.is_compiled(Path::new(&ly_file), &pdfs_directory) {
+            statistics.compiled += 1;
+        }
+
+        if let Some(version) = metadata.lilypond_version {
+            if is_outdated(&version, &global_version) {
+                statistics.behind_global += 1;
+            }
+
+            statistics.versions.add(&version);
+        } else {
+            statistics.versions.add("none");
+        }
+
+        for helper in get_helpers(&score) {
+            statistics.helpers.add(&helper);
+        }
+
+        statistics
+            .keys
+            .add(&metadata.key.unwrap_or_else(|| "unknown".to_string()));
+        statistics
+            .times
+            .add(&metadata.time.unwrap_or_else(|| "unknown".to_string()));
+    }
+
+    if statistics.scores == 0 {
+        println!("No scores found.");
+
+        return;
+    }
+
+    print_summary(&statistics, &global_version);
+    statistics.artists.print("Artist");
+    statistics.templates.print("Template");
+    statistics.versions.print("LilyPond Version");
+    statistics.helpers.print("Helper");
+    statistics.keys.print("Key");
+    statistics.times.print("Time Signature");
+}
@@ -1,7 +1,24 @@
 use bat::{PagingMode, PrettyPrinter};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::{Map, Value};
 use tabled::{builder::Builder, settings::Style};
 
-pub fn print(titles: &[String], rows: Vec<Vec<String>>) {
+use super::OutputFormat;
+
+static ANSI_ESCAPE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\x1b\[[0-9;]*m").expect("Failed to create ANSI regex.")
+});
+
+fn strip_ansi(value: &str) -> String {
+    ANSI_ESCAPE.replace_all(value, "").to_string()
+}
+
+fn strip_ansi_values(values: &[String]) -> Vec<String> {
+    values.iter().map(|value| strip_ansi(value)).collect()
+}
+
+fn print_table(titles: &[String], rows: Vec<Vec<String>>) {
     let mut builder = Builder::default();
 
     if !titles.is_empty() {
@@ -29,3 +46,130 @@ pub fn print(titles: &[String], rows: Vec<Vec<String>>) {
         .print()
         .expect("Failed to print table.");
 }
+
+fn get_json(titles: &[String], rows: &[Vec<String>]) -> String {
+    let values: Vec<Value> = if titles.is_empty() {
+        rows.iter()
+            .map(|row| {
+                Value::Array(row.iter().cloned().map(Value::String).collect())
+            })
+            .collect()
+    } else {
+        rows.iter()
+            .map(|row| {
+                let object: Map<String, Value> = titles
+                    .iter()
+                    .zip(row)
+                    .map(|(title, value)| {
+                        (
+                            title.to_lowercase().replace(' ', "_"),
+                            Value::String(value.to_string()),
+                        )
+                    })
+                    .collect();
+
+                Value::Object(object)
+            })
+            .collect()
+    };
+
+    serde_json::to_string_pretty(&values)
+        .expect("Failed to serialize table as JSON.")
+}
+
+fn escape_csv_value(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn get_csv_line(values: &[String]) -> String {
+    values
+        .iter()
+        .map(|value| escape_csv_value(value))
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+fn get_csv(titles: &[String], rows: &[Vec<String>]) -> String {
+    let mut lines = vec![];
+
+    if !titles.is_empty() {
+        lines.push(get_csv_line(titles));
+    }
+
+    for row in rows {
+        lines.push(get_csv_line(row));
+    }
+
+    lines.join("\n")
+}
+
+fn get_markdown_line(values: &[String]) -> String {
+    let values: Vec<String> = values
+        .iter()
+        .map(|value| value.replace('|', "\\|"))
+        .collect();
+
+    format!("| {} |", values.join(" | "))
+}
+
+fn get_markdown(titles: &[String], rows: &[Vec<String>]) -> String {
+    let columns = rows
+        .iter()
+        .map(Vec::len)
+        .chain([titles.len()])
+        .max()
+        .unwrap_or_default();
+
+    let header = if titles.is_empty() {
+        vec![String::new(); columns]
+    } else {
+        titles.to_vec()
+    };
+
+    let mut lines = vec![
+        get_markdown_line(&header),
+        get_markdown_line(&vec!["---".to_string(); columns]),
+    ];
+
+    for row in rows {
+        lines.push(get_markdown_line(row));
+    }
+
+    lines.join("\n")
+}
+
+fn get_plain(rows: &[Vec<String>]) -> String {
+    rows.iter()
+        .map(|row| row.join("\t"))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub fn print(
+    titles: &[String],
+    rows: Vec<Vec<String>>,
+    format: &Option<OutputFormat>,
+) {
+    let Some(format) = format else {
+        print_table(titles, rows);
+
+        return;
+    };
+
+    let titles = strip_ansi_values(titles);
+    let rows: Vec<Vec<String>> =
+        rows.iter().map(|row| strip_ansi_values(row)).collect();
+
+    let output = match format {
+        OutputFormat::Csv => get_csv(&titles, &rows),
+        OutputFormat::Json => get_json(&titles, &rows),
+        OutputFormat::Markdown => get_markdown(&titles, &rows),
+        OutputFormat::Plain => get_plain(&rows),
+    };
+
+    println!("{output}");
+}
@@ -1,39 +1,124 @@
+pub mod big_band;
+pub mod choir;
+pub mod custom;
+pub mod ensemble;
 pub mod form;
 pub mod lead;
 pub mod piano;
+pub mod preview;
+pub mod rhythm_section;
+pub mod settings;
 pub mod single;
+pub mod string_quartet;
 
 use std::fmt;
-use std::process::Command;
+use std::path::Path;
+use std::str::FromStr;
 
 use bat::{PagingMode, PrettyPrinter};
-use clap::ValueEnum;
 use fmt::{Display, Formatter, Result};
 use indoc::formatdoc;
 use owo_colors::OwoColorize;
-use regex::Regex;
 use serde::{Deserialize, Serialize};
 
+use self::big_band::get_big_band_templates;
+use self::choir::get_choir_templates;
+use self::custom::{
+    find_custom_template, get_custom_template_files, get_custom_templates,
+};
 use self::form::get_form_templates;
 use self::lead::get_lead_templates;
 use self::piano::get_piano_template;
+use self::rhythm_section::get_rhythm_section_templates;
+use self::settings::TemplateSettings;
 use self::single::get_single_template;
-use super::{table, TemplateCommand};
-use crate::commands::{
-    add_value_to_string_if_some, create::get_file_system_name,
+use self::string_quartet::get_string_quartet_templates;
+use super::{table, OutputFormat, TemplateCommand};
+use crate::commands::add_value_to_string_if_some;
+use crate::commands::create::{
+    get_file_system_name, get_template_settings, ScoreFileSettings,
 };
 
-#[derive(Clone, Debug, Deserialize, Serialize, ValueEnum)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
 pub enum Template {
+    BigBand,
+    Choir,
     Form,
     Lead,
     Piano,
+    RhythmSection,
     Single,
+    StringQuartet,
+    Custom(String),
 }
 
 impl Display for Template {
     fn fmt(&self, formatter: &mut Formatter) -> Result {
-        write!(formatter, "{self:?}")
+        match self {
+            Self::Custom(name) => write!(formatter, "{name}"),
+            _ => write!(formatter, "{self:?}"),
+        }
+    }
+}
+
+/// Accepts the built-in templates, and any other name as a custom template,
+/// which can only be looked up once the scores directory is known (see
+/// `Template::check_exists`).
+impl FromStr for Template {
+    type Err = String;
+
+    fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+        match value.to_lowercase().replace(['-', '_'], "").as_str() {
+            "bigband" => Ok(Self::BigBand),
+            "choir" => Ok(Self::Choir),
+            "form" => Ok(Self::Form),
+            "lead" => Ok(Self::Lead),
+            "piano" => Ok(Self::Piano),
+            "rhythmsection" => Ok(Self::RhythmSection),
+            "single" => Ok(Self::Single),
+            "stringquartet" => Ok(Self::StringQuartet),
+            _ if value.trim().is_empty() => {
+                Err("Template name can't be empty.".to_string())
+            }
+            _ => Ok(Self::Custom(value.to_string())),
+        }
+    }
+}
+
+impl Template {
+    /// Built-in templates always exist, and custom templates are looked up in
+    /// `scores_directory` and the config directory.
+    pub fn check_exists(
+        &self,
+        scores_directory: &Option<String>,
+    ) -> std::result::Result<(), String> {
+        match self {
+            Self::Custom(name)
+                if find_custom_template(name, scores_directory).is_none() =>
+            {
+                Err(get_not_found_message(self))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+pub fn get_not_found_message(template: &Template) -> String {
+    format!(
+        "Template \"{template}\" not found. Run `thoth templates` to see the available templates."
+    )
+}
+
+impl From<String> for Template {
+    fn from(value: String) -> Self {
+        value.parse().unwrap_or(Self::Custom(value))
+    }
+}
+
+impl From<Template> for String {
+    fn from(template: Template) -> Self {
+        template.to_string()
     }
 }
 
@@ -43,39 +128,21 @@ pub struct TemplateFile {
     pub content: String,
 }
 
-fn get_lilypond_version() -> String {
-    let err = "Failed to run lilypond command.";
-    let output = String::from_utf8(
-        Command::new("lilypond")
-            .arg("--version")
-            .output()
-            .expect(err)
-            .stdout,
-    )
-    .expect(err);
-
-    let pattern = Regex::new(r"\d\.\d{2}\.\d")
-        .expect("Failed to create lilypond version regex.");
-    let err = "Failed to find lilypond version.";
-
-    let found = pattern
-        .captures_iter(&output)
-        .next()
-        .expect(err)
-        .get(0)
-        .expect(err)
-        .as_str()
-        .to_owned();
-
-    format!("\\version \"{found}\"")
-}
-
-fn get_header(
-    title: &String,
-    subtitle: &Option<String>,
-    composer: &String,
-    arranger: &Option<String>,
-) -> String {
+pub fn get_lilypond_version(settings: &TemplateSettings) -> String {
+    let version = &settings.lilypond_version;
+
+    format!("\\version \"{version}\"")
+}
+
+pub fn get_header(settings: &TemplateSettings) -> String {
+    let TemplateSettings {
+        title,
+        subtitle,
+        composer,
+        arranger,
+        ..
+    } = settings;
+
     let mut header = formatdoc!(
         "
         \\header {{
@@ -91,13 +158,23 @@ fn get_header(
     header
 }
 
+/// Parts are `.ily` files, unless `filename` has an extension of its own, as
+/// extra `.ly` files in custom templates do.
+pub fn get_part_file_name(filename: &str) -> String {
+    if Path::new(filename).extension().is_some() {
+        filename.to_string()
+    } else {
+        format!("{filename}.ily")
+    }
+}
+
 fn format_filename(filename: &Option<String>, title: &str) -> String {
     let filename = filename.as_ref().map_or_else(
         || {
             let title = get_file_system_name(title);
             format!("{title}.ly")
         },
-        |filename| format!("{filename}.ily"),
+        |filename| get_part_file_name(filename),
     );
 
     let lines = "-".repeat(filename.len());
@@ -105,7 +182,7 @@ fn format_filename(filename: &Option<String>, title: &str) -> String {
     format!("% {lines}\n% {filename}\n% {lines}\n\n")
 }
 
-fn print_templates(templates: &[TemplateFile], title: &str) {
+pub fn print_templates(templates: &[TemplateFile], title: &str) {
     let mut lines = String::default();
 
     for (index, template) in templates.iter().enumerate() {
@@ -127,43 +204,96 @@ fn print_templates(templates: &[TemplateFile], title: &str) {
         .expect("Failed to print template.");
 }
 
+pub fn get_templates(
+    settings: &TemplateSettings,
+    template: &Template,
+    scores_directory: &Option<String>,
+) -> Option<Vec<TemplateFile>> {
+    let templates = match template {
+        Template::BigBand => get_big_band_templates(settings),
+        Template::Choir => get_choir_templates(settings),
+        Template::Form => get_form_templates(settings),
+        Template::Lead => get_lead_templates(settings),
+        Template::Piano => get_piano_template(settings),
+        Template::RhythmSection => get_rhythm_section_templates(settings),
+        Template::Single => get_single_template(settings),
+        Template::StringQuartet => get_string_quartet_templates(settings),
+        Template::Custom(name) => get_custom_template_files(
+            &find_custom_template(name, scores_directory)?,
+            settings,
+        ),
+    };
+
+    Some(templates)
+}
+
+pub fn get_sample_settings() -> ScoreFileSettings {
+    ScoreFileSettings {
+        title: "Title".to_string(),
+        subtitle: Some("Subtitle".to_string()),
+        composer: Some("Composer".to_string()),
+        arranger: Some("Arranger".to_string()),
+        instrument: Some("Instrument".to_string()),
+        ..ScoreFileSettings::default()
+    }
+}
+
 fn show_template(template: &Template) {
-    let title = &"Title".to_string();
-    let subtitle = &Some("Subtitle".to_string());
-    let composer = &"Composer".to_string();
-    let arranger = &Some("Arranger".to_string());
-    let instrument = &"Instrument".to_string();
-
-    match template {
-        Template::Form => {
-            let templates =
-                get_form_templates(title, subtitle, composer, arranger);
-            print_templates(&templates, title);
-        }
-        Template::Lead => {
-            let templates = get_lead_templates(
-                title, subtitle, composer, arranger, instrument,
-            );
-            print_templates(&templates, title);
-        }
-        Template::Piano => {
-            let templates =
-                get_piano_template(title, subtitle, composer, arranger);
-            print_templates(&templates, title);
-        }
-        Template::Single => {
-            let templates = get_single_template(
-                title, subtitle, composer, arranger, instrument,
-            );
-            print_templates(&templates, title);
+    let settings = match get_template_settings(&get_sample_settings(), &None) {
+        Ok(settings) => settings,
+        Err(message) => {
+            println!("{message}");
+
+            return;
         }
+    };
+
+    if let Some(templates) = get_templates(&settings, template, &None) {
+        print_templates(&templates, &settings.title);
+    } else {
+        println!("{}", get_not_found_message(template));
     }
 }
 
-pub fn main(command: &Option<TemplateCommand>) {
+pub fn get_template_descriptions(
+    scores_directory: &Option<String>,
+) -> Vec<(String, String)> {
+    let mut descriptions: Vec<(String, String)> = [
+        (
+            "BigBand",
+            "Big band with saxophone, trumpet, trombone and rhythm sections",
+        ),
+        ("Choir", "SATB choir with lyrics"),
+        (
+            "Form",
+            "Form chart with separate sections and form summary at the bottom",
+        ),
+        ("Lead", "Lead sheet showing melody and chords"),
+        ("Piano", "Piano staff score"),
+        ("RhythmSection", "Chords, guitar, piano, bass and drums"),
+        ("Single", "Score for a single staff instrumenta"),
+        ("StringQuartet", "Two violins, viola and cello"),
+    ]
+    .iter()
+    .map(|(name, description)| (name.to_string(), description.to_string()))
+    .collect();
+
+    for template in get_custom_templates(scores_directory) {
+        descriptions.push((template.name, template.description));
+    }
+
+    descriptions
+}
+
+pub fn main(command: &Option<TemplateCommand>, format: &Option<OutputFormat>) {
     if command.is_some() {
         match command.as_ref().expect("Failed to get command.") {
             TemplateCommand::Show { template } => show_template(template),
+            TemplateCommand::Preview {
+                template,
+                png,
+                lilypond_version,
+            } => preview::main(template, png, lilypond_version),
         }
 
         return;
@@ -173,30 +303,13 @@ pub fn main(command: &Option<TemplateCommand>) {
         "Name".italic().to_string(),
         "Description".italic().to_string(),
     ];
-    let rows = [
-        [
-            "Form".yellow().to_string(),
-            "Form chart with separate sections and form summary at the bottom"
-                .to_string(),
-        ],
-        [
-            "Lead".yellow().to_string(),
-            "Lead sheet showing melody and chords".to_string(),
-        ],
-        [
-            "Piano".yellow().to_string(),
-            "Piano staff score".to_string(),
-        ],
-        [
-            "Single".yellow().to_string(),
-            "Score for a single staff instrumenta".to_string(),
-        ],
-    ];
 
-    let rows = rows
-        .iter()
-        .map(|row| row.iter().map(ToString::to_string).collect())
+    let rows: Vec<Vec<String>> = get_template_descriptions(&None)
+        .into_iter()
+        .map(|(name, description)| {
+            vec![name.yellow().to_string(), description]
+        })
         .collect();
 
-    table::print(&titles, rows);
+    table::print(&titles, rows, format);
 }
@@ -0,0 +1,194 @@
+use indoc::formatdoc;
+
+use crate::commands::templates::ensemble::{
+    get_includes, get_key_and_time, get_parts_music, get_staff_group, indent,
+    Part,
+};
+use crate::commands::templates::rhythm_section::{
+    get_rhythm_section_parts, get_rhythm_section_staves,
+    RHYTHM_SECTION_FILENAMES,
+};
+use crate::commands::templates::settings::TemplateSettings;
+use crate::commands::templates::{
+    get_header, get_lilypond_version, TemplateFile,
+};
+
+const SAXOPHONES: [Part; 5] = [
+    Part {
+        variable: "alto_sax_one",
+        name: "Alto Sax. 1 in E♭",
+        short_name: "A. Sx. 1",
+        clef: "treble",
+        relative: "c'",
+        transposition: Some("ef c'"),
+    },
+    Part {
+        variable: "alto_sax_two",
+        name: "Alto Sax. 2 in E♭",
+        short_name: "A. Sx. 2",
+        clef: "treble",
+        relative: "c'",
+        transposition: Some("ef c'"),
+    },
+    Part {
+        variable: "tenor_sax_one",
+        name: "Tenor Sax. 1 in B♭",
+        short_name: "T. Sx. 1",
+        clef: "treble",
+        relative: "c",
+        transposition: Some("bf, c'"),
+    },
+    Part {
+        variable: "tenor_sax_two",
+        name: "Tenor Sax. 2 in B♭",
+        short_name: "T. Sx. 2",
+        clef: "treble",
+        relative: "c",
+        transposition: Some("bf, c'"),
+    },
+    Part {
+        variable: "baritone_sax",
+        name: "Baritone Sax. in E♭",
+        short_name: "B. Sx.",
+        clef: "treble",
+        relative: "c",
+        transposition: Some("ef, c'"),
+    },
+];
+
+const TRUMPETS: [Part; 4] = [
+    Part {
+        variable: "trumpet_one",
+        name: "Trumpet 1 in B♭",
+        short_name: "Tpt. 1",
+        clef: "treble",
+        relative: "c''",
+        transposition: Some("bf c'"),
+    },
+    Part {
+        variable: "trumpet_two",
+        name: "Trumpet 2 in B♭",
+        short_name: "Tpt. 2",
+        clef: "treble",
+        relative: "c''",
+        transposition: Some("bf c'"),
+    },
+    Part {
+        variable: "trumpet_three",
+        name: "Trumpet 3 in B♭",
+        short_name: "Tpt. 3",
+        clef: "treble",
+        relative: "c'",
+        transposition: Some("bf c'"),
+    },
+    Part {
+        variable: "trumpet_four",
+        name: "Trumpet 4 in B♭",
+        short_name: "Tpt. 4",
+        clef: "treble",
+        relative: "c'",
+        transposition: Some("bf c'"),
+    },
+];
+
+const TROMBONES: [Part; 4] = [
+    Part {
+        variable: "trombone_one",
+        name: "Trombone 1",
+        short_name: "Tbn. 1",
+        clef: "bass",
+        relative: "c'",
+        transposition: None,
+    },
+    Part {
+        variable: "trombone_two",
+        name: "Trombone 2",
+        short_name: "Tbn. 2",
+        clef: "bass",
+        relative: "c'",
+        transposition: None,
+    },
+    Part {
+        variable: "trombone_three",
+        name: "Trombone 3",
+        short_name: "Tbn. 3",
+        clef: "bass",
+        relative: "c",
+        transposition: None,
+    },
+    Part {
+        variable: "bass_trombone",
+        name: "Bass Trombone",
+        short_name: "B. Tbn.",
+        clef: "bass",
+        relative: "c",
+        transposition: None,
+    },
+];
+
+const HORN_SECTIONS: [(&str, &[Part]); 3] = [
+    ("saxophones", &SAXOPHONES),
+    ("trumpets", &TRUMPETS),
+    ("trombones", &TROMBONES),
+];
+
+fn get_big_band_main(settings: &TemplateSettings) -> String {
+    let lilypond_version = get_lilypond_version(settings);
+    let header = get_header(settings);
+    let paper = &settings.paper;
+    let key_and_time = get_key_and_time(settings);
+    let filenames: Vec<&str> = HORN_SECTIONS
+        .iter()
+        .map(|(filename, _)| *filename)
+        .chain(RHYTHM_SECTION_FILENAMES)
+        .collect();
+    let includes = get_includes(&settings.file_name, &filenames);
+    let mut staves: Vec<String> = HORN_SECTIONS
+        .iter()
+        .map(|(_, parts)| get_staff_group("StaffGroup", parts))
+        .collect();
+    staves.push(get_rhythm_section_staves());
+    let staves = indent(&staves.join("\n"), 4);
+
+    formatdoc!(
+        "
+        {lilypond_version}
+
+        \\include \"helpers/settings.ily\"
+
+        {paper}
+
+        {header}
+
+        {key_and_time}
+
+        {includes}
+
+        \\score {{
+          <<
+        {staves}
+          >>
+        }}
+"
+    )
+}
+
+pub fn get_big_band_templates(
+    settings: &TemplateSettings,
+) -> Vec<TemplateFile> {
+    let mut templates = vec![TemplateFile {
+        filename: None,
+        content: get_big_band_main(settings),
+    }];
+
+    for (filename, parts) in HORN_SECTIONS {
+        templates.push(TemplateFile {
+            filename: Some(filename.to_string()),
+            content: get_parts_music(parts),
+        });
+    }
+
+    templates.append(&mut get_rhythm_section_parts());
+
+    templates
+}
@@ -0,0 +1,143 @@
+use indoc::formatdoc;
+
+use crate::commands::templates::ensemble::{
+    get_includes, get_key_and_time, indent,
+};
+use crate::commands::templates::settings::TemplateSettings;
+use crate::commands::templates::{
+    get_header, get_lilypond_version, TemplateFile,
+};
+
+struct Voice {
+    variable: &'static str,
+    name: &'static str,
+    short_name: &'static str,
+    clef: &'static str,
+    relative: &'static str,
+}
+
+const VOICES: [Voice; 4] = [
+    Voice {
+        variable: "soprano",
+        name: "Soprano",
+        short_name: "S.",
+        clef: "treble",
+        relative: "c''",
+    },
+    Voice {
+        variable: "alto",
+        name: "Alto",
+        short_name: "A.",
+        clef: "treble",
+        relative: "c'",
+    },
+    Voice {
+        variable: "tenor",
+        name: "Tenor",
+        short_name: "T.",
+        clef: "\"treble_8\"",
+        relative: "c'",
+    },
+    Voice {
+        variable: "bass",
+        name: "Bass",
+        short_name: "B.",
+        clef: "bass",
+        relative: "c",
+    },
+];
+
+fn get_choir_part(voice: &Voice) -> String {
+    let Voice {
+        variable, relative, ..
+    } = voice;
+
+    formatdoc!(
+        "
+        {variable}_music = \\relative {relative} {{
+          \\key_and_time
+          | c1
+        }}
+
+        {variable}_words = \\lyricmode {{
+          Ah
+        }}
+"
+    )
+}
+
+fn get_choir_staff(voice: &Voice) -> String {
+    let Voice {
+        variable,
+        name,
+        short_name,
+        clef,
+        ..
+    } = voice;
+
+    formatdoc!(
+        "
+        \\new Staff = \"{variable}\" \\with {{
+          instrumentName = \"{name}\"
+          shortInstrumentName = \"{short_name}\"
+        }} {{
+          \\clef {clef}
+          \\new Voice = \"{variable}\" \\{variable}_music
+        }}
+        \\new Lyrics \\lyricsto \"{variable}\" \\{variable}_words"
+    )
+}
+
+fn get_choir_main(settings: &TemplateSettings) -> String {
+    let lilypond_version = get_lilypond_version(settings);
+    let header = get_header(settings);
+    let paper = &settings.paper;
+    let key_and_time = get_key_and_time(settings);
+    let filenames: Vec<&str> =
+        VOICES.iter().map(|voice| voice.variable).collect();
+    let includes = get_includes(&settings.file_name, &filenames);
+    let staves = VOICES
+        .iter()
+        .map(get_choir_staff)
+        .collect::<Vec<String>>()
+        .join("\n");
+    let staves = indent(&staves, 4);
+
+    formatdoc!(
+        "
+        {lilypond_version}
+
+        \\include \"helpers/settings.ily\"
+
+        {paper}
+
+        {header}
+
+        {key_and_time}
+
+        {includes}
+
+        \\score {{
+          \\new ChoirStaff <<
+        {staves}
+          >>
+        }}
+"
+    )
+}
+
+pub fn get_choir_templates(settings: &TemplateSettings) -> Vec<TemplateFile> {
+    let mut templates = vec![TemplateFile {
+        filename: None,
+        content: get_choir_main(settings),
+    }];
+
+    for voice in &VOICES {
+        templates.push(TemplateFile {
+            filename: Some(voice.variable.to_string()),
+            content: get_choir_part(voice),
+        });
+    }
+
+    templates
+}
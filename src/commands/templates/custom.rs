@@ -0,0 +1,212 @@
+use std::ffi::OsStr;
+use std::fs::{read_dir, read_to_string};
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use shellexpand::tilde;
+use toml::from_str;
+
+use crate::commands::get_scores_directory_from_arg;
+use crate::commands::templates::settings::TemplateSettings;
+use crate::commands::templates::{get_header, TemplateFile};
+
+static CONFIG_TEMPLATES_PATH: &str = "~/.config/thoth/templates";
+static TEMPLATE_SETTINGS_FILE: &str = "template.toml";
+
+#[derive(Debug, Default, Deserialize)]
+struct CustomTemplateSettings {
+    description: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct CustomTemplate {
+    pub name: String,
+    pub description: String,
+    pub path: PathBuf,
+}
+
+impl CustomTemplate {
+    fn from_path(path: PathBuf) -> Option<Self> {
+        if !path.is_dir() {
+            return None;
+        }
+
+        let name = path.file_name()?.to_str()?.to_string();
+        let settings_path = path.join(TEMPLATE_SETTINGS_FILE);
+
+        let settings: CustomTemplateSettings = read_to_string(settings_path)
+            .ok()
+            .and_then(|settings| from_str(&settings).ok())
+            .unwrap_or_default();
+
+        let description = settings.description.unwrap_or_else(|| {
+            format!("Custom template ({})", path.display())
+        });
+
+        Some(Self {
+            name,
+            description,
+            path,
+        })
+    }
+}
+
+fn get_template_directories(scores_directory: &Option<String>) -> Vec<String> {
+    let scores_directory = get_scores_directory_from_arg(scores_directory);
+
+    vec![
+        format!("{scores_directory}/templates"),
+        tilde(CONFIG_TEMPLATES_PATH).to_string(),
+    ]
+}
+
+/// Templates in the scores repository take precedence over templates with
+/// the same name in the config directory.
+pub fn get_custom_templates(
+    scores_directory: &Option<String>,
+) -> Vec<CustomTemplate> {
+    let mut templates: Vec<CustomTemplate> = vec![];
+
+    for directory in get_template_directories(scores_directory) {
+        let Ok(entries) = read_dir(&directory) else {
+            continue;
+        };
+
+        let mut directory_templates: Vec<CustomTemplate> = entries
+            .flatten()
+            .filter_map(|entry| CustomTemplate::from_path(entry.path()))
+            .filter(|template| {
+                !templates
+                    .iter()
+                    .any(|existing| existing.name == template.name)
+            })
+            .collect();
+
+        templates.append(&mut directory_templates);
+    }
+
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+
+    templates
+}
+
+pub fn find_custom_template(
+    name: &str,
+    scores_directory: &Option<String>,
+) -> Option<CustomTemplate> {
+    get_custom_templates(scores_directory)
+        .into_iter()
+        .find(|template| template.name == name)
+}
+
+fn get_extension(path: &Path) -> Option<&str> {
+    path.extension().and_then(|extension| extension.to_str())
+}
+
+fn get_name(name: Option<&OsStr>) -> String {
+    name.and_then(|name| name.to_str())
+        .expect("Failed to parse custom template file name.")
+        .to_string()
+}
+
+fn fill_placeholders(content: &str, settings: &TemplateSettings) -> String {
+    let TemplateSettings {
+        title,
+        file_name,
+        subtitle,
+        composer,
+        arranger,
+        instrument,
+        key,
+        time,
+        tempo,
+        clef,
+        pickup,
+        paper,
+        lilypond_version,
+    } = settings;
+
+    [
+        ("title", title.to_string()),
+        ("subtitle", subtitle.clone().unwrap_or_default()),
+        ("composer", composer.to_string()),
+        ("arranger", arranger.clone().unwrap_or_default()),
+        ("instrument", instrument.to_string()),
+        ("key", key.to_string()),
+        ("time", time.to_string()),
+        ("tempo", tempo.clone().unwrap_or_default()),
+        ("clef", clef.to_string()),
+        ("pickup", pickup.clone().unwrap_or_default()),
+        ("paper", paper.to_string()),
+        ("version", lilypond_version.to_string()),
+        ("header", get_header(settings)),
+        ("file_name", file_name.to_string()),
+    ]
+    .iter()
+    .fold(content.to_string(), |content, (placeholder, value)| {
+        content.replace(&format!("{{{{{placeholder}}}}}"), value)
+    })
+}
+
+/// The template's main score file is the `.ly` file named after the template,
+/// or `main.ly`, or else the first `.ly` file. Other `.ly` files keep their
+/// names, and every `.ily` file is a part named after its file stem.
+/// Placeholders
+/// such as `{{title}}`, `{{key}}` or `{{version}}` are replaced with the
+/// score's values, with `{{key}}`, `{{time}}`, `{{tempo}}`, `{{clef}}`,
+/// `{{pickup}}` and `{{paper}}` filled in as LilyPond commands.
+pub fn get_custom_template_files(
+    template: &CustomTemplate,
+    settings: &TemplateSettings,
+) -> Vec<TemplateFile> {
+    let Ok(entries) = read_dir(&template.path) else {
+        return vec![];
+    };
+
+    let mut paths: Vec<PathBuf> =
+        entries.flatten().map(|entry| entry.path()).collect();
+
+    paths.sort();
+
+    let ly_files: Vec<&PathBuf> = paths
+        .iter()
+        .filter(|path| get_extension(path) == Some("ly"))
+        .collect();
+    let main_file = [template.name.as_str(), "main"]
+        .iter()
+        .find_map(|name| {
+            ly_files
+                .iter()
+                .find(|path| get_name(path.file_stem()) == *name)
+        })
+        .or_else(|| ly_files.first())
+        .copied();
+
+    let mut templates = vec![];
+
+    for path in &paths {
+        let filename = match get_extension(path) {
+            Some("ly") if Some(path) == main_file => None,
+            Some("ly") => Some(get_name(path.file_name())),
+            Some("ily") => Some(get_name(path.file_stem())),
+            _ => continue,
+        };
+
+        let content = read_to_string(path).unwrap_or_else(|err| {
+            panic!("Failed to read {} ({err})", path.display())
+        });
+
+        let template = TemplateFile {
+            filename,
+            content: fill_placeholders(&content, settings),
+        };
+
+        if template.filename.is_none() {
+            templates.insert(0, template);
+        } else {
+            templates.push(template);
+        }
+    }
+
+    templates
+}
@@ -0,0 +1,109 @@
+use indoc::formatdoc;
+
+use crate::commands::templates::settings::TemplateSettings;
+
+pub struct Part {
+    pub variable: &'static str,
+    pub name: &'static str,
+    pub short_name: &'static str,
+    pub clef: &'static str,
+    pub relative: &'static str,
+    pub transposition: Option<&'static str>,
+}
+
+impl Part {
+    pub fn get_music(&self) -> String {
+        let Self {
+            variable, relative, ..
+        } = self;
+
+        formatdoc!(
+            "
+            {variable} = \\relative {relative} {{
+              \\key_and_time
+              | c1
+            }}
+"
+        )
+    }
+
+    pub fn get_staff(&self) -> String {
+        let Self {
+            variable,
+            name,
+            short_name,
+            clef,
+            ..
+        } = self;
+
+        let music = self.transposition.map_or_else(
+            || format!("\\{variable}"),
+            |transposition| {
+                format!("\\transpose {transposition} \\{variable}")
+            },
+        );
+
+        formatdoc!(
+            "
+            \\new Staff \\with {{
+              instrumentName = \"{name}\"
+              shortInstrumentName = \"{short_name}\"
+            }} {{
+              \\clef {clef}
+              {music}
+            }}"
+        )
+    }
+}
+
+pub fn get_parts_music(parts: &[Part]) -> String {
+    parts
+        .iter()
+        .map(Part::get_music)
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub fn get_staff_group(context: &str, parts: &[Part]) -> String {
+    let staves = parts
+        .iter()
+        .map(Part::get_staff)
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    format!("\\new {context} <<\n{}\n>>", indent(&staves, 2).trim_end())
+}
+
+pub fn get_key_and_time(settings: &TemplateSettings) -> String {
+    let key_and_time = settings.get_key_and_time(2);
+
+    formatdoc!(
+        "
+        key_and_time = {{
+          {key_and_time}
+        }}"
+    )
+}
+
+pub fn get_includes(file_name: &str, filenames: &[&str]) -> String {
+    filenames
+        .iter()
+        .map(|filename| format!("\\include \"{file_name}-{filename}.ily\""))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub fn indent(text: &str, spaces: usize) -> String {
+    let indentation = " ".repeat(spaces);
+
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{indentation}{line}")
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
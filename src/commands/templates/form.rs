@@ -1,5 +1,6 @@
 use indoc::formatdoc;
 
+use crate::commands::templates::settings::TemplateSettings;
 use crate::commands::templates::{
     get_header, get_lilypond_version, TemplateFile,
 };
@@ -18,14 +19,10 @@ fn get_form_changes() -> String {
     )
 }
 
-fn get_form_main(
-    title: &String,
-    subtitle: &Option<String>,
-    composer: &String,
-    arranger: &Option<String>,
-) -> String {
-    let lilypond_version = get_lilypond_version();
-    let header = get_header(title, subtitle, composer, arranger);
+fn get_form_main(settings: &TemplateSettings) -> String {
+    let lilypond_version = get_lilypond_version(settings);
+    let header = get_header(settings);
+    let paper = &settings.paper;
 
     formatdoc!(
         "
@@ -37,6 +34,8 @@ fn get_form_main(
         \\include \"changes.ily\"
         \\include \"structure.ily\"
 
+        {paper}
+
         \\paper {{
           score-system-spacing.basic-distance = #18
         }}
@@ -118,12 +117,15 @@ fn get_form_melody() -> String {
     )
 }
 
-fn get_form_structure() -> String {
+fn get_form_structure(settings: &TemplateSettings) -> String {
+    let clef = &settings.clef;
+    let key_and_time = settings.get_key_and_time(2);
+
     formatdoc!(
         "
         key_and_time = {{
-          \\key c \\major
-          \time 4/4
+          {clef}
+          {key_and_time}
         }}
 
         structure_verse = {{
@@ -141,16 +143,11 @@ fn get_form_structure() -> String {
     )
 }
 
-pub fn get_form_templates(
-    title: &String,
-    subtitle: &Option<String>,
-    composer: &String,
-    arranger: &Option<String>,
-) -> Vec<TemplateFile> {
+pub fn get_form_templates(settings: &TemplateSettings) -> Vec<TemplateFile> {
     vec![
         TemplateFile {
             filename: None,
-            content: get_form_main(title, subtitle, composer, arranger),
+            content: get_form_main(settings),
         },
         TemplateFile {
             filename: Some("changes".to_string()),
@@ -162,7 +159,7 @@ pub fn get_form_templates(
         },
         TemplateFile {
             filename: Some("structure".to_string()),
-            content: get_form_structure(),
+            content: get_form_structure(settings),
         },
     ]
 }
@@ -1,23 +1,28 @@
 use indoc::formatdoc;
 
+use crate::commands::templates::ensemble::get_includes;
+use crate::commands::templates::settings::TemplateSettings;
 use crate::commands::templates::{
     get_header, get_lilypond_version, TemplateFile,
 };
 
+pub const LEAD_FILENAMES: [&str; 4] =
+    ["changes", "lyrics", "melody", "structure"];
+
 fn get_lead_changes() -> String {
     formatdoc!(
         "
-        verse = \\chordmode {{
-          | c1
+        changes_verse = \\chordmode {{
+          | c1 | c1 | c1 | c1
         }}
 
-        chorus = \\chordmode {{
-          | c1
+        changes_chorus = \\chordmode {{
+          | c1 | c1 | c1 | c1
         }}
 
-        changes = \\chords {{
-          \\verse
-          \\chorus
+        changes = \\chordmode {{
+          \\changes_verse
+          \\changes_chorus
         }}
 "
     )
@@ -26,54 +31,57 @@ fn get_lead_changes() -> String {
 fn get_lead_lyrics() -> String {
     formatdoc!(
         "
-        verse = \\lyricmode {{
+        words_verse = \\lyricmode {{
           Verse one
         }}
 
-        chorus = \\lyricmode {{
+        words_chorus = \\lyricmode {{
           Chorus
         }}
 
         words = \\lyricmode {{
-          \\verse
-          \\chorus
+          \\words_verse
+          \\words_chorus
         }}
 "
     )
 }
 
-fn get_lead_main(
-    title: &String,
-    subtitle: &Option<String>,
-    composer: &String,
-    arranger: &Option<String>,
-    instrument: &String,
-) -> String {
-    let lilypond_version = get_lilypond_version();
-    let header = get_header(title, subtitle, composer, arranger);
+fn get_lead_main(settings: &TemplateSettings) -> String {
+    let lilypond_version = get_lilypond_version(settings);
+    let header = get_header(settings);
+    let paper = &settings.paper;
+    let instrument = &settings.instrument;
+    let clef = &settings.clef;
+    let includes = get_includes(&settings.file_name, &LEAD_FILENAMES);
 
     formatdoc!(
         "
         {lilypond_version}
 
         \\include \"helpers/settings.ily\"
+        {includes}
 
-        {header}
+        {paper}
 
-        music = \\relative c'' {{
-            \\key c \\major
-            \\time 4/4
-            | c1
-        }}
+        {header}
 
         \\score {{
-            \\new Staff \\with {{
-                instrumentName = \"{instrument}\"
-                \\numericTimeSignature
-            }} {{
-                \\compressMMRests
-                \\music
-            }}
+            <<
+                \\new ChordNames \\changes
+                \\new Staff \\with {{
+                    instrumentName = \"{instrument}\"
+                    \\numericTimeSignature
+                }} <<
+                    \\structure
+                    \\new Voice = \"melody\" {{
+                        {clef}
+                        \\compressMMRests
+                        \\melody
+                    }}
+                >>
+                \\new Lyrics \\lyricsto \"melody\" \\words
+            >>
         }}
 "
     )
@@ -82,60 +90,53 @@ fn get_lead_main(
 fn get_lead_melody() -> String {
     formatdoc!(
         "
-        verse = \\relative c'' {{
-          | c1
+        melody_verse = \\relative c'' {{
+          | c1 | c1 | c1 | c1
         }}
 
-        chorus = \\relative c'' {{
-          | c1
+        melody_chorus = \\relative c'' {{
+          | c1 | c1 | c1 | c1
         }}
 
         melody = {{
-          \\verse
-          \\chorus
+          \\melody_verse
+          \\melody_chorus
         }}
 "
     )
 }
 
-fn get_lead_structure() -> String {
+fn get_lead_structure(settings: &TemplateSettings) -> String {
+    let key_and_time = settings.get_key_and_time(2);
+
     formatdoc!(
         "
         key_and_time = {{
-          \\key c \\major
-          \\time 4/4
+          {key_and_time}
         }}
 
-        verse = {{
+        structure_verse = {{
           | s1 * 4
         }}
 
-        chorus = {{
+        structure_chorus = {{
           | s1 * 4
         }}
 
         structure = {{
           \\key_and_time
-          \\verse
-          \\chorus
+          \\structure_verse
+          \\structure_chorus
         }}
 "
     )
 }
 
-pub fn get_lead_templates(
-    title: &String,
-    subtitle: &Option<String>,
-    composer: &String,
-    arranger: &Option<String>,
-    instrument: &String,
-) -> Vec<TemplateFile> {
+pub fn get_lead_templates(settings: &TemplateSettings) -> Vec<TemplateFile> {
     vec![
         TemplateFile {
             filename: None,
-            content: get_lead_main(
-                title, subtitle, composer, arranger, instrument,
-            ),
+            content: get_lead_main(settings),
         },
         TemplateFile {
             filename: Some("changes".to_string()),
@@ -151,7 +152,7 @@ pub fn get_lead_templates(
         },
         TemplateFile {
             filename: Some("structure".to_string()),
-            content: get_lead_structure(),
+            content: get_lead_structure(settings),
         },
     ]
 }
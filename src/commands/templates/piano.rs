@@ -1,17 +1,15 @@
 use indoc::formatdoc;
 
+use crate::commands::templates::settings::TemplateSettings;
 use crate::commands::templates::{
     get_header, get_lilypond_version, TemplateFile,
 };
 
-pub fn get_piano_template(
-    title: &String,
-    subtitle: &Option<String>,
-    composer: &String,
-    arranger: &Option<String>,
-) -> Vec<TemplateFile> {
-    let lilypond_version = get_lilypond_version();
-    let header = get_header(title, subtitle, composer, arranger);
+pub fn get_piano_template(settings: &TemplateSettings) -> Vec<TemplateFile> {
+    let lilypond_version = get_lilypond_version(settings);
+    let header = get_header(settings);
+    let paper = &settings.paper;
+    let key_and_time = settings.get_key_and_time(2);
 
     let content = formatdoc!(
         "
@@ -19,11 +17,12 @@ pub fn get_piano_template(
 
         \\include \"helpers/settings.ily\"
 
+        {paper}
+
         {header}
 
         key_and_time = {{
-          \\key c \\major
-          \\time 4/4
+          {key_and_time}
         }}
 
         upper_staff = \\relative c'' {{
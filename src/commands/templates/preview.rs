@@ -0,0 +1,136 @@
+use std::fs::{copy, create_dir_all, remove_dir_all};
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::Command;
+
+use crate::commands::compile::get_lilypond_command;
+use crate::commands::create::{
+    create_file, get_file_system_name, get_template_settings,
+};
+use crate::commands::helpers::write_helper_files;
+use crate::commands::open::open_file;
+use crate::commands::scores::TEMPORARY_DIRECTORY;
+use crate::commands::templates::{
+    get_not_found_message, get_sample_settings, get_templates, Template,
+};
+
+fn get_preview_directory(template: &Template) -> String {
+    let name = get_file_system_name(&template.to_string());
+
+    format!("{TEMPORARY_DIRECTORY}/preview/{name}")
+}
+
+fn compile_preview(
+    file: &str,
+    directory: &str,
+    png: bool,
+    lilypond_version: &Option<String>,
+) -> bool {
+    let mut command =
+        Command::new(get_lilypond_command(file, lilypond_version));
+
+    if png {
+        command.arg("--png");
+    }
+
+    match command
+        .args(["--include", directory])
+        .args(["--output", directory])
+        .arg(file)
+        .output()
+    {
+        Ok(output) => {
+            if !output.status.success() {
+                io::stdout()
+                    .write_all(output.stderr.as_ref())
+                    .expect("Failed to print lilypond command output.");
+            }
+
+            output.status.success()
+        }
+        Err(error) => {
+            println!("Error: {error}");
+            false
+        }
+    }
+}
+
+fn save_png(directory: &str, stem: &str, png: &str) {
+    let single_page = format!("{directory}/{stem}.png");
+    let first_page = format!("{directory}/{stem}-page1.png");
+
+    let source = if Path::new(&single_page).exists() {
+        single_page
+    } else {
+        first_page
+    };
+
+    match copy(&source, png) {
+        Ok(_) => println!("Saved {png}"),
+        Err(error) => println!("Failed to save {png} ({error})"),
+    }
+}
+
+pub fn main(
+    template: &Template,
+    png: &Option<String>,
+    lilypond_version: &Option<String>,
+) {
+    let settings = match get_template_settings(
+        &get_sample_settings(),
+        lilypond_version,
+    ) {
+        Ok(settings) => settings,
+        Err(message) => {
+            println!("{message}");
+
+            return;
+        }
+    };
+
+    let Some(templates) = get_templates(&settings, template, &None) else {
+        println!("{}", get_not_found_message(template));
+
+        return;
+    };
+
+    let directory = get_preview_directory(template);
+    let _ = remove_dir_all(&directory);
+
+    create_dir_all(&directory)
+        .expect("Failed to create template preview directory.");
+    write_helper_files(&directory).expect("Failed to write helper files.");
+
+    let stem = get_file_system_name(&settings.title);
+    let mut main_file = None;
+
+    for template in templates {
+        let is_main_file = template.filename.is_none();
+        let file = create_file(template, &directory, stem.clone(), true);
+
+        if is_main_file {
+            main_file = Some(file);
+        }
+    }
+
+    let Some(main_file) = main_file else {
+        println!("Template \"{template}\" has no main score file.");
+
+        return;
+    };
+
+    if !compile_preview(
+        &main_file,
+        &directory,
+        png.is_some(),
+        lilypond_version,
+    ) {
+        return;
+    }
+
+    if let Some(png) = png {
+        save_png(&directory, &stem, png);
+    } else {
+        open_file(Path::new(&format!("{directory}/{stem}.pdf")));
+    }
+}
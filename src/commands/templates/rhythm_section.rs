@@ -0,0 +1,157 @@
+use indoc::formatdoc;
+
+use crate::commands::templates::ensemble::{
+    get_includes, get_key_and_time, indent, Part,
+};
+use crate::commands::templates::settings::TemplateSettings;
+use crate::commands::templates::{
+    get_header, get_lilypond_version, TemplateFile,
+};
+
+pub const RHYTHM_SECTION_FILENAMES: [&str; 5] =
+    ["changes", "guitar", "piano", "bass", "drums"];
+
+const GUITAR: Part = Part {
+    variable: "guitar",
+    name: "Guitar",
+    short_name: "Gtr.",
+    clef: "\"treble_8\"",
+    relative: "c'",
+    transposition: None,
+};
+
+const BASS: Part = Part {
+    variable: "bass",
+    name: "Bass",
+    short_name: "Bs.",
+    clef: "\"bass_8\"",
+    relative: "c",
+    transposition: None,
+};
+
+fn get_changes() -> String {
+    formatdoc!(
+        "
+        changes = \\chordmode {{
+          | c1
+        }}
+"
+    )
+}
+
+fn get_piano() -> String {
+    formatdoc!(
+        "
+        piano_upper = \\relative c'' {{
+          \\key_and_time
+          | c1
+        }}
+
+        piano_lower = \\relative c {{
+          \\key_and_time
+          | c1
+        }}
+"
+    )
+}
+
+fn get_drums() -> String {
+    formatdoc!(
+        "
+        drums = \\drummode {{
+          | bd4 sn bd sn
+        }}
+"
+    )
+}
+
+pub fn get_rhythm_section_staves() -> String {
+    let guitar = GUITAR.get_staff();
+    let bass = BASS.get_staff();
+
+    formatdoc!(
+        "
+        \\new ChordNames \\changes
+        {guitar}
+        \\new PianoStaff \\with {{
+          instrumentName = \"Piano\"
+          shortInstrumentName = \"Pno.\"
+        }} <<
+          \\new Staff = \"upper\" \\piano_upper
+          \\new Staff = \"lower\" {{
+            \\clef bass
+            \\piano_lower
+          }}
+        >>
+        {bass}
+        \\new DrumStaff \\with {{
+          instrumentName = \"Drums\"
+          shortInstrumentName = \"Dr.\"
+        }} \\drums"
+    )
+}
+
+pub fn get_rhythm_section_parts() -> Vec<TemplateFile> {
+    RHYTHM_SECTION_FILENAMES
+        .iter()
+        .map(|filename| {
+            let content = match *filename {
+                "changes" => get_changes(),
+                "guitar" => GUITAR.get_music(),
+                "piano" => get_piano(),
+                "bass" => BASS.get_music(),
+                _ => get_drums(),
+            };
+
+            TemplateFile {
+                filename: Some((*filename).to_string()),
+                content,
+            }
+        })
+        .collect()
+}
+
+fn get_rhythm_section_main(settings: &TemplateSettings) -> String {
+    let lilypond_version = get_lilypond_version(settings);
+    let header = get_header(settings);
+    let paper = &settings.paper;
+    let key_and_time = get_key_and_time(settings);
+    let includes =
+        get_includes(&settings.file_name, &RHYTHM_SECTION_FILENAMES);
+    let staves = indent(&get_rhythm_section_staves(), 4);
+
+    formatdoc!(
+        "
+        {lilypond_version}
+
+        \\include \"helpers/settings.ily\"
+
+        {paper}
+
+        {header}
+
+        {key_and_time}
+
+        {includes}
+
+        \\score {{
+          <<
+        {staves}
+          >>
+        }}
+"
+    )
+}
+
+pub fn get_rhythm_section_templates(
+    settings: &TemplateSettings,
+) -> Vec<TemplateFile> {
+    let mut templates = vec![TemplateFile {
+        filename: None,
+        content: get_rhythm_section_main(settings),
+    }];
+
+    templates.append(&mut get_rhythm_section_parts());
+
+    templates
+}
@@ -0,0 +1,163 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static KEY: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)^\s*([a-g])\s*(sharp|flat|s|f|b|#|♯|♭)?\s*\\?(major|minor|m|ionian|dorian|phrygian|lydian|mixolydian|aeolian|locrian)?\s*$",
+    )
+    .expect("Failed to create key regex.")
+});
+
+static TIME: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\s*(\d+(?:\+\d+)*)\s*/\s*(\d+)\s*$")
+        .expect("Failed to create time signature regex.")
+});
+
+static TEMPO: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\s*(?:(.*?)\s+)?(\d+\.?)\s*=\s*(\d+(?:\s*-\s*\d+)?)\s*$")
+        .expect("Failed to create tempo regex.")
+});
+
+static CLEF: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^[a-zA-Z]+([_^]\d+)?$").expect("Failed to create clef regex.")
+});
+
+static DURATION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(1|2|4|8|16|32|64|128)\.*(\*\d+(/\d+)?)?$")
+        .expect("Failed to create duration regex.")
+});
+
+static PAPER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^[\w-]+$").expect("Failed to create paper regex.")
+});
+
+pub struct TemplateSettings {
+    pub title: String,
+    /// Stem of the score's files, e.g. "title" or "title-2".
+    pub file_name: String,
+    pub subtitle: Option<String>,
+    pub composer: String,
+    pub arranger: Option<String>,
+    pub instrument: String,
+    pub key: String,
+    pub time: String,
+    pub tempo: Option<String>,
+    pub clef: String,
+    pub pickup: Option<String>,
+    pub paper: String,
+    pub lilypond_version: String,
+}
+
+impl TemplateSettings {
+    /// Key, time signature, tempo and pickup, one command per line, with
+    /// every line after the first indented by `indentation` spaces.
+    pub fn get_key_and_time(&self, indentation: usize) -> String {
+        let mut lines = vec![self.key.to_string(), self.time.to_string()];
+
+        if let Some(tempo) = &self.tempo {
+            lines.push(tempo.to_string());
+        }
+
+        if let Some(pickup) = &self.pickup {
+            lines.push(pickup.to_string());
+        }
+
+        lines.join(&format!("\n{}", " ".repeat(indentation)))
+    }
+}
+
+pub fn parse_key(key: &str) -> Result<String, String> {
+    let captures = KEY
+        .captures(key)
+        .ok_or_else(|| format!("Unrecognized key: \"{key}\""))?;
+
+    let tonic = captures[1].to_lowercase();
+
+    let accidental =
+        captures.get(2).map_or("", |accidental| {
+            match accidental.as_str().to_lowercase().as_str() {
+                "sharp" | "s" | "#" | "♯" => "s",
+                _ => "f",
+            }
+        });
+
+    let mode = match captures
+        .get(3)
+        .map_or_else(
+            || "major".to_string(),
+            |mode| mode.as_str().to_lowercase(),
+        )
+        .as_str()
+    {
+        "m" => "minor".to_string(),
+        mode => mode.to_string(),
+    };
+
+    Ok(format!("\\key {tonic}{accidental} \\{mode}"))
+}
+
+pub fn parse_time(time: &str) -> Result<String, String> {
+    let captures = TIME
+        .captures(time)
+        .ok_or_else(|| format!("Unrecognized time signature: \"{time}\""))?;
+
+    Ok(format!("\\time {}/{}", &captures[1], &captures[2]))
+}
+
+pub fn parse_tempo(tempo: &str) -> Result<String, String> {
+    let tempo = tempo.trim();
+
+    if tempo.is_empty() {
+        return Err("Tempo cannot be empty.".to_string());
+    }
+
+    if tempo.chars().all(|char| char.is_ascii_digit()) {
+        return Ok(format!("\\tempo 4 = {tempo}"));
+    }
+
+    Ok(TEMPO.captures(tempo).map_or_else(
+        || format!("\\tempo \"{tempo}\""),
+        |captures| {
+            let text = captures.get(1).map_or_else(String::new, |text| {
+                format!("\"{}\" ", text.as_str())
+            });
+            let bpm = captures[3].replace(' ', "");
+
+            format!("\\tempo {text}{} = {bpm}", &captures[2])
+        },
+    ))
+}
+
+pub fn parse_clef(clef: &str) -> Result<String, String> {
+    let clef = clef.trim().trim_matches('"');
+
+    let Some(captures) = CLEF.captures(clef) else {
+        return Err(format!("Unrecognized clef: \"{clef}\""));
+    };
+
+    if captures.get(1).is_some() {
+        Ok(format!("\\clef \"{clef}\""))
+    } else {
+        Ok(format!("\\clef {clef}"))
+    }
+}
+
+pub fn parse_pickup(pickup: &str) -> Result<String, String> {
+    let pickup = pickup.trim();
+
+    if DURATION.is_match(pickup) {
+        Ok(format!("\\partial {pickup}"))
+    } else {
+        Err(format!("Unrecognized pickup duration: \"{pickup}\""))
+    }
+}
+
+pub fn parse_paper(paper: &str) -> Result<String, String> {
+    let paper = paper.trim();
+
+    if PAPER.is_match(paper) {
+        Ok(format!("\\paperBlock \"{paper}\""))
+    } else {
+        Err(format!("Unrecognized paper size: \"{paper}\""))
+    }
+}
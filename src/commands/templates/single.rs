@@ -1,18 +1,17 @@
 use indoc::formatdoc;
 
+use crate::commands::templates::settings::TemplateSettings;
 use crate::commands::templates::{
     get_header, get_lilypond_version, TemplateFile,
 };
 
-pub fn get_single_template(
-    title: &String,
-    subtitle: &Option<String>,
-    composer: &String,
-    arranger: &Option<String>,
-    instrument: &String,
-) -> Vec<TemplateFile> {
-    let lilypond_version = get_lilypond_version();
-    let header = get_header(title, subtitle, composer, arranger);
+pub fn get_single_template(settings: &TemplateSettings) -> Vec<TemplateFile> {
+    let lilypond_version = get_lilypond_version(settings);
+    let header = get_header(settings);
+    let paper = &settings.paper;
+    let instrument = &settings.instrument;
+    let clef = &settings.clef;
+    let key_and_time = settings.get_key_and_time(4);
 
     let content = formatdoc!(
         "
@@ -21,11 +20,13 @@ pub fn get_single_template(
         \\include \"helpers/settings.ily\"
         \\include \"helpers/bar-numbers-left.ily\"
 
+        {paper}
+
         {header}
 
         music = \\relative c'' {{
-            \\key c \\major
-            \\time 4/4
+            {clef}
+            {key_and_time}
             | c1
         }}
 
@@ -0,0 +1,97 @@
+use indoc::formatdoc;
+
+use crate::commands::templates::ensemble::{
+    get_includes, get_key_and_time, get_staff_group, indent, Part,
+};
+use crate::commands::templates::settings::TemplateSettings;
+use crate::commands::templates::{
+    get_header, get_lilypond_version, TemplateFile,
+};
+
+const PARTS: [Part; 4] = [
+    Part {
+        variable: "violin_one",
+        name: "Violin I",
+        short_name: "Vln. I",
+        clef: "treble",
+        relative: "c''",
+        transposition: None,
+    },
+    Part {
+        variable: "violin_two",
+        name: "Violin II",
+        short_name: "Vln. II",
+        clef: "treble",
+        relative: "c''",
+        transposition: None,
+    },
+    Part {
+        variable: "viola",
+        name: "Viola",
+        short_name: "Vla.",
+        clef: "alto",
+        relative: "c'",
+        transposition: None,
+    },
+    Part {
+        variable: "cello",
+        name: "Violoncello",
+        short_name: "Vc.",
+        clef: "bass",
+        relative: "c",
+        transposition: None,
+    },
+];
+
+fn get_filename(part: &Part) -> String {
+    part.variable.replace('_', "-")
+}
+
+fn get_string_quartet_main(settings: &TemplateSettings) -> String {
+    let lilypond_version = get_lilypond_version(settings);
+    let header = get_header(settings);
+    let paper = &settings.paper;
+    let key_and_time = get_key_and_time(settings);
+    let filenames: Vec<String> = PARTS.iter().map(get_filename).collect();
+    let filenames: Vec<&str> = filenames.iter().map(String::as_str).collect();
+    let includes = get_includes(&settings.file_name, &filenames);
+    let staff_group = indent(&get_staff_group("StaffGroup", &PARTS), 2);
+
+    formatdoc!(
+        "
+        {lilypond_version}
+
+        \\include \"helpers/settings.ily\"
+
+        {paper}
+
+        {header}
+
+        {key_and_time}
+
+        {includes}
+
+        \\score {{
+        {staff_group}
+        }}
+"
+    )
+}
+
+pub fn get_string_quartet_templates(
+    settings: &TemplateSettings,
+) -> Vec<TemplateFile> {
+    let mut templates = vec![TemplateFile {
+        filename: None,
+        content: get_string_quartet_main(settings),
+    }];
+
+    for part in &PARTS {
+        templates.push(TemplateFile {
+            filename: Some(get_filename(part)),
+            content: part.get_music(),
+        });
+    }
+
+    templates
+}
@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+
+use super::launcher::open_in_editor_with_fallback;
+
+/// Most editors that thoth doesn't know accept a `+line` argument, which is
+/// better than opening the file at the top.
+const POSITION_FALLBACK: &str = "+{line} {file}";
+
+/// A source position from a point-and-click link.
+struct Location {
+    file: PathBuf,
+    line: usize,
+    column: Option<usize>,
+}
+
+pub fn decode_percent_escapes(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = vec![];
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let escape = bytes
+            .get(index + 1..index + 3)
+            .filter(|_| bytes[index] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        if let Some(byte) = escape {
+            decoded.push(byte);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// Parses `textedit:///path:line:char:column`, where `char` is the 0-based
+/// character offset on the line.
+fn parse_uri(uri: &str) -> Option<Location> {
+    let path = decode_percent_escapes(uri.strip_prefix("textedit://")?);
+    let mut parts: Vec<&str> = path.rsplitn(4, ':').collect();
+    parts.reverse();
+
+    let (file, numbers) = parts.split_first()?;
+    let numbers = numbers
+        .iter()
+        .map(|number| number.parse::<usize>().ok())
+        .collect::<Option<Vec<usize>>>()?;
+    let line = *numbers.first()?;
+    let column = numbers.get(1).map(|char| char + 1);
+
+    Some(Location {
+        file: PathBuf::from(file),
+        line,
+        column,
+    })
+}
+
+pub fn main(uri: &str) {
+    let Some(location) = parse_uri(uri) else {
+        println!("\"{uri}\" is not a textedit link.");
+        return;
+    };
+
+    if !location.file.exists() {
+        println!("File not found: {}", location.file.display());
+        return;
+    }
+
+    open_in_editor_with_fallback(
+        &location.file,
+        Some(location.line),
+        location.column,
+        Some(POSITION_FALLBACK),
+    );
+}
@@ -0,0 +1,531 @@
+use std::fs::{create_dir_all, read_to_string, write};
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use glob::glob;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+use super::compile::{compile_input_file, CompileOptions};
+use super::create::get_file_system_name;
+use super::scores::get_selected_ly_file;
+use super::templates::ensemble::indent;
+use super::templates::settings::parse_key;
+
+static KEY: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\\key\s+([a-g](?:ss|ff|s|f)?)\s")
+        .expect("Failed to create key regex.")
+});
+
+static INTERVAL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?i:(up|down)\s+)?([+-])?([PMmAd])(\d+)$")
+        .expect("Failed to create interval regex.")
+});
+
+static SEMITONES: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^([+-])?(\d+)$").expect("Failed to create semitones regex.")
+});
+
+static INCLUDE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\\include\s+"([^"]+)""#)
+        .expect("Failed to create include regex.")
+});
+
+static INSTRUMENT_NAME: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"instrumentName\s*=\s*"[^"]*""#)
+        .expect("Failed to create instrument name regex.")
+});
+
+static HEADER_INSTRUMENT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?m)^\s*instrument\s*=\s*"[^"]*"\n"#)
+        .expect("Failed to create header instrument regex.")
+});
+
+static NATURAL_SEMITONES: [i32; 7] = [0, 2, 4, 5, 7, 9, 11];
+static NOTE_NAMES: [char; 7] = ['c', 'd', 'e', 'f', 'g', 'a', 'b'];
+
+/// Spelling used for transpositions given in semitones.
+static CHROMATIC: [(i32, i32); 12] = [
+    (0, 0),
+    (1, -1),
+    (1, 0),
+    (2, -1),
+    (2, 0),
+    (3, 0),
+    (3, 1),
+    (4, 0),
+    (5, -1),
+    (5, 0),
+    (6, -1),
+    (6, 0),
+];
+
+#[derive(Clone, Debug, ValueEnum)]
+pub enum Instrument {
+    AltoSax,
+    BaritoneSax,
+    BassClarinet,
+    BbClarinet,
+    BbTrumpet,
+    EbClarinet,
+    FHorn,
+    SopranoSax,
+    TenorSax,
+}
+
+impl Instrument {
+    fn get_name(&self) -> &str {
+        match self {
+            Self::AltoSax => "Alto Saxophone in E♭",
+            Self::BaritoneSax => "Baritone Saxophone in E♭",
+            Self::BassClarinet => "Bass Clarinet in B♭",
+            Self::BbClarinet => "Clarinet in B♭",
+            Self::BbTrumpet => "Trumpet in B♭",
+            Self::EbClarinet => "Clarinet in E♭",
+            Self::FHorn => "Horn in F",
+            Self::SopranoSax => "Soprano Saxophone in B♭",
+            Self::TenorSax => "Tenor Saxophone in B♭",
+        }
+    }
+
+    /// Concert pitch to written pitch.
+    fn get_transposition(&self) -> (&str, &str) {
+        match self {
+            Self::AltoSax => ("ef", "c'"),
+            Self::BaritoneSax => ("ef,", "c'"),
+            Self::BassClarinet | Self::TenorSax => ("bf,", "c'"),
+            Self::BbClarinet | Self::BbTrumpet | Self::SopranoSax => {
+                ("bf", "c'")
+            }
+            Self::EbClarinet => ("ef", "c"),
+            Self::FHorn => ("f", "c'"),
+        }
+    }
+
+    fn get_file_system_name(&self) -> String {
+        self.to_possible_value()
+            .map(|value| value.get_name().to_string())
+            .expect("Failed to get instrument name.")
+    }
+}
+
+pub struct Transposition {
+    pub to: Option<String>,
+    pub instrument: Option<Instrument>,
+}
+
+#[derive(Clone, Copy)]
+struct Pitch {
+    step: i32,
+    alteration: i32,
+    octave: i32,
+}
+
+impl Pitch {
+    fn from_steps(steps: i32, semitones: i32) -> Self {
+        let step = steps.rem_euclid(7);
+        let octave = steps.div_euclid(7);
+
+        Self {
+            step,
+            alteration: semitones
+                - NATURAL_SEMITONES[step as usize]
+                - 12 * octave,
+            octave,
+        }
+    }
+
+    fn parse(name: &str) -> Option<Self> {
+        let mut chars = name.chars();
+        let letter = chars.next()?;
+        let step = NOTE_NAMES.iter().position(|note| *note == letter)?;
+        let accidentals = chars.as_str();
+
+        let alteration = match accidentals {
+            "" => 0,
+            "s" => 1,
+            "ss" => 2,
+            "f" => -1,
+            "ff" => -2,
+            _ => return None,
+        };
+
+        Some(Self {
+            step: step as i32,
+            alteration,
+            octave: 0,
+        })
+    }
+
+    fn get_semitones(self) -> i32 {
+        NATURAL_SEMITONES[self.step as usize]
+            + self.alteration
+            + 12 * self.octave
+    }
+
+    fn to_lilypond(self) -> String {
+        let accidental = if self.alteration >= 0 { "s" } else { "f" };
+        let octave = if self.octave >= 0 { "'" } else { "," };
+
+        format!(
+            "{}{}{}",
+            NOTE_NAMES[self.step as usize],
+            accidental.repeat(self.alteration.unsigned_abs() as usize),
+            octave.repeat(self.octave.unsigned_abs() as usize)
+        )
+    }
+}
+
+fn get_interval_semitones(quality: &str, number: i32) -> Option<i32> {
+    let steps = number - 1;
+    let base = NATURAL_SEMITONES[steps.rem_euclid(7) as usize]
+        + 12 * steps.div_euclid(7);
+    let is_perfect = matches!(steps.rem_euclid(7), 0 | 3 | 4);
+
+    let adjustment = match (quality, is_perfect) {
+        ("P", true) | ("M", false) => 0,
+        ("m", false) => -1,
+        ("A", _) => 1,
+        ("d", true) => -1,
+        ("d", false) => -2,
+        _ => return None,
+    };
+
+    Some(base + adjustment)
+}
+
+fn parse_interval(interval: &str) -> Option<(Pitch, i32)> {
+    if let Some(captures) = SEMITONES.captures(interval) {
+        let sign = if captures.get(1).map(|sign| sign.as_str()) == Some("-") {
+            -1
+        } else {
+            1
+        };
+        let semitones = sign * captures[2].parse::<i32>().ok()?;
+        let (step, _) = CHROMATIC[semitones.rem_euclid(12) as usize];
+        let steps = step + 7 * semitones.div_euclid(12);
+
+        return Some((Pitch::from_steps(steps, semitones), semitones));
+    }
+
+    let captures = INTERVAL.captures(interval)?;
+    let is_down = captures.get(1).is_some_and(|direction| {
+        direction.as_str().eq_ignore_ascii_case("down")
+    }) || captures.get(2).map(|sign| sign.as_str()) == Some("-");
+    let number = captures[4]
+        .parse::<i32>()
+        .ok()
+        .filter(|number| *number > 0)?;
+    let semitones = get_interval_semitones(&captures[3], number)?;
+    let sign = if is_down { -1 } else { 1 };
+
+    Some((
+        Pitch::from_steps(sign * (number - 1), sign * semitones),
+        sign * semitones,
+    ))
+}
+
+/// The target tonic in the octave closest to the source tonic.
+fn get_key_pitch(from: Pitch, key: &str) -> Option<(Pitch, String)> {
+    let key = parse_key(key).ok()?;
+    let mut parts = key.split_whitespace().skip(1);
+    let tonic = parts.next()?;
+    let mode = parts.next()?.trim_start_matches('\\');
+    let mut pitch = Pitch::parse(tonic)?;
+
+    let difference = pitch.get_semitones() - from.get_semitones();
+
+    if difference > 6 {
+        pitch.octave -= 1;
+    } else if difference < -6 {
+        pitch.octave += 1;
+    }
+
+    Some((pitch, format!("{tonic}-{mode}")))
+}
+
+/// "bf-major" as "B♭ major".
+fn get_key_name(key: &str) -> String {
+    let (tonic, mode) = key.split_once('-').unwrap_or((key, ""));
+    let mut chars = tonic.chars();
+    let letter = chars
+        .next()
+        .map(|letter| letter.to_ascii_uppercase())
+        .unwrap_or_default();
+    let accidentals = chars.as_str().replace('s', "♯").replace('f', "♭");
+
+    format!("{letter}{accidentals} {mode}")
+}
+
+/// The `\transpose` command, directory suffix and header description for a
+/// key (relative to the score's key) or an interval.
+fn get_target(to: &str, from: Pitch) -> Option<(String, String, String)> {
+    if let Some((pitch, semitones)) = parse_interval(to) {
+        let direction = if semitones < 0 { "down" } else { "up" };
+        let semitones = semitones.abs();
+
+        return Some((
+            format!("\\transpose c {}", pitch.to_lilypond()),
+            format!("{direction}-{semitones}"),
+            format!("Transposed {direction} {semitones} semitones"),
+        ));
+    }
+
+    let (pitch, suffix) = get_key_pitch(from, to)?;
+    let description = format!("in {}", get_key_name(&suffix));
+
+    Some((
+        format!("\\transpose {} {}", from.to_lilypond(), pitch.to_lilypond()),
+        suffix,
+        description,
+    ))
+}
+
+fn get_score_files(directory: &Path) -> Vec<PathBuf> {
+    glob(&format!("{}/*.*ly", directory.display()))
+        .expect("Failed to read glob pattern")
+        .flatten()
+        .collect()
+}
+
+fn get_source_key(directory: &Path) -> Pitch {
+    get_score_files(directory)
+        .iter()
+        .filter_map(|file| read_to_string(file).ok())
+        .find_map(|content| {
+            KEY.captures(&content)
+                .and_then(|captures| Pitch::parse(&captures[1]))
+        })
+        .unwrap_or(Pitch {
+            step: 0,
+            alteration: 0,
+            octave: 0,
+        })
+}
+
+fn get_score_end(content: &str, start: usize) -> (usize, usize) {
+    let mut depth = 0;
+    let mut music_end = None;
+
+    for (index, char) in content[start..].char_indices() {
+        let position = start + index;
+
+        match char {
+            '{' => depth += 1,
+            '}' if depth == 0 => {
+                return (music_end.unwrap_or(position), position)
+            }
+            '}' => depth -= 1,
+            '\\' if depth == 0 && music_end.is_none() => {
+                let rest = &content[position..];
+
+                if ["\\layout", "\\midi", "\\header"]
+                    .iter()
+                    .any(|keyword| rest.starts_with(keyword))
+                {
+                    music_end = Some(position);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    (music_end.unwrap_or(content.len()), content.len())
+}
+
+/// Wraps the music expression of every `\score` block in `transpose`,
+/// leaving any `\layout`, `\midi` or `\header` blocks in place.
+fn transpose_scores(content: &str, transpose: &str) -> String {
+    let mut transposed = String::new();
+    let mut rest = content;
+
+    while let Some(index) = rest.find("\\score") {
+        let after_keyword = &rest[index + "\\score".len()..];
+        let Some(brace) = after_keyword.find('{') else {
+            break;
+        };
+
+        if !after_keyword[..brace].trim().is_empty() {
+            transposed.push_str(&rest[..index + "\\score".len()]);
+            rest = after_keyword;
+            continue;
+        }
+
+        let start = index + "\\score".len() + brace + 1;
+        let (music_end, score_end) = get_score_end(rest, start);
+        let music = &rest[start..music_end];
+        let leading = &music[..music.len() - music.trim_start().len()];
+        let trailing = &music[music.trim_end().len()..];
+        let indentation = leading.rsplit('\n').next().unwrap_or_default();
+        let body = indent(&format!("{indentation}{}", music.trim()), 2);
+
+        transposed.push_str(&rest[..start]);
+        transposed.push_str(&format!(
+            "{leading}{transpose} {{\n{body}\n{indentation}}}{trailing}"
+        ));
+        transposed.push_str(&rest[music_end..score_end]);
+        rest = &rest[score_end..];
+    }
+
+    transposed.push_str(rest);
+    transposed
+}
+
+/// Prefixes every include except the helpers with `prefix`, for files
+/// generated outside of the score's directory.
+pub fn update_includes(content: &str, prefix: &str) -> String {
+    INCLUDE
+        .replace_all(content, |captures: &Captures| {
+            let file = &captures[1];
+
+            if file.starts_with("helpers/") {
+                captures[0].to_string()
+            } else {
+                format!("\\include \"{prefix}{file}\"")
+            }
+        })
+        .to_string()
+}
+
+pub fn update_header(content: &str, instrument: &str) -> String {
+    let content = HEADER_INSTRUMENT.replace_all(content, "");
+
+    content.replacen(
+        "\\header {",
+        &format!("\\header {{\n  instrument = \"{instrument}\""),
+        1,
+    )
+}
+
+fn update_instrument_name(content: &str, instrument: &str) -> String {
+    if INSTRUMENT_NAME.find_iter(content).count() == 1 {
+        INSTRUMENT_NAME
+            .replace(content, format!("instrumentName = \"{instrument}\""))
+            .to_string()
+    } else {
+        content.to_string()
+    }
+}
+
+fn create_variant(
+    ly_file: &str,
+    transposition: &Transposition,
+    force: bool,
+) -> Result<String, String> {
+    let ly_file = Path::new(ly_file);
+    let directory = ly_file.parent().expect("Failed to get score directory.");
+    let directory_name = directory
+        .file_name()
+        .and_then(|name| name.to_str())
+        .expect("Failed to get score directory name.");
+    let stem = ly_file
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .expect("Failed to get score file name.");
+
+    let mut transpositions = vec![];
+    let mut suffixes = vec![];
+    let mut descriptions = vec![];
+
+    if let Some(to) = &transposition.to {
+        let (transpose, suffix, description) =
+            get_target(to, get_source_key(directory)).ok_or_else(|| {
+                format!("Unrecognized key or interval: \"{to}\"")
+            })?;
+
+        transpositions.push(transpose);
+        suffixes.push(get_file_system_name(&suffix));
+        descriptions.push(description);
+    }
+
+    if let Some(instrument) = &transposition.instrument {
+        let (from, to) = instrument.get_transposition();
+
+        transpositions.insert(0, format!("\\transpose {from} {to}"));
+        suffixes.push(instrument.get_file_system_name());
+        descriptions.insert(0, instrument.get_name().to_string());
+    }
+
+    if transpositions.is_empty() {
+        return Err(
+            "Specify a key or interval with --to, or an --instrument."
+                .to_string(),
+        );
+    }
+
+    let suffix = suffixes.join("-");
+    let variant_directory =
+        directory.with_file_name(format!("{directory_name}-{suffix}"));
+
+    if !force && variant_directory.exists() {
+        return Err(format!(
+            "\"{}\" already exists. Use --force to overwrite it.",
+            variant_directory.display()
+        ));
+    }
+
+    let content = read_to_string(ly_file).map_err(|err| {
+        format!("Failed to read {} ({err})", ly_file.display())
+    })?;
+    let description = descriptions.join(", ");
+
+    let mut content = transpose_scores(&content, &transpositions.join(" "));
+    content = update_includes(&content, &format!("../{directory_name}/"));
+    content = update_header(&content, &description);
+
+    if let Some(instrument) = &transposition.instrument {
+        content = update_instrument_name(&content, instrument.get_name());
+    }
+
+    create_dir_all(&variant_directory)
+        .expect("Failed to create transposed score directory.");
+
+    let variant_file = variant_directory.join(format!("{stem}-{suffix}.ly"));
+
+    write(&variant_file, content).map_err(|err| {
+        format!("Failed to write {} ({err})", variant_file.display())
+    })?;
+
+    Ok(variant_file
+        .to_str()
+        .expect("Failed to parse file path.")
+        .to_string())
+}
+
+pub fn main(
+    search_term: &String,
+    search_artist: bool,
+    search_title: bool,
+    transposition: &Transposition,
+    force: bool,
+    scores_directory: &Option<String>,
+    pdfs_directory: &Option<String>,
+) {
+    let Some(ly_file) = get_selected_ly_file(
+        search_term,
+        search_artist,
+        search_title,
+        scores_directory,
+    ) else {
+        println!("No score found matching \"{search_term}\".");
+        return;
+    };
+
+    match create_variant(&ly_file, transposition, force) {
+        Ok(file) => {
+            println!("Created {file}");
+
+            let _ = compile_input_file(
+                &PathBuf::from(file),
+                &None,
+                scores_directory,
+                pdfs_directory,
+                &CompileOptions {
+                    force: true,
+                    ..Default::default()
+                },
+            );
+        }
+        Err(message) => println!("{message}"),
+    }
+}
@@ -0,0 +1,607 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::remove_file;
+use std::mem::take;
+use std::path::{Path, PathBuf};
+
+use tuikit::prelude::*;
+
+use super::compile::{compile_input_file, CompileOptions};
+use super::info::get_composition_metadata;
+use super::launcher::{
+    open_in_editor, open_in_pdf_viewer, uses_terminal_editor,
+};
+use super::scores::{get_score_ly_file, search};
+use super::update_version::{get_new_version, update_file_version};
+use super::{get_pdfs_directory_from_arg, get_scores_directory_from_arg};
+
+const LOG_HEIGHT: usize = 10;
+
+const HELP: &str = "↑/↓ move  enter fold  c compile  o open  e edit  d clean  u update version  r refresh  q quit";
+
+#[derive(Clone, Copy)]
+enum Status {
+    Compiled,
+    Outdated,
+    Uncompiled,
+}
+
+impl Status {
+    const fn get_symbol(&self) -> (&'static str, Color) {
+        match self {
+            Self::Compiled => ("✓", Color::GREEN),
+            Self::Outdated => ("!", Color::YELLOW),
+            Self::Uncompiled => ("·", Color::LIGHT_BLACK),
+        }
+    }
+
+    const fn get_description(&self) -> &'static str {
+        match self {
+            Self::Compiled => "compiled",
+            Self::Outdated => "out of date",
+            Self::Uncompiled => "not compiled",
+        }
+    }
+}
+
+struct Score {
+    title: String,
+    ly_file: PathBuf,
+    /// Cached so that redrawing doesn't search the pdfs directory.
+    status: Status,
+}
+
+impl Score {
+    fn get_stem(&self) -> String {
+        self.ly_file
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default()
+            .to_string()
+    }
+}
+
+enum Row<'a> {
+    Artist(&'a str),
+    Score(&'a Score),
+}
+
+struct App {
+    artists: BTreeMap<String, Vec<Score>>,
+    collapsed: BTreeSet<String>,
+    selected: usize,
+    log: String,
+    lilypond_version: Option<String>,
+    scores_directory: String,
+    pdfs_directory: String,
+    options: CompileOptions<'static>,
+    /// Set by `d` until the next key answers the y/n prompt in the log.
+    confirming_clean: bool,
+}
+
+fn get_artists(
+    search_terms: &Vec<String>,
+    search_artist: bool,
+    search_title: bool,
+    scores_directory: &Option<String>,
+) -> BTreeMap<String, Vec<Score>> {
+    let mut artists: BTreeMap<String, Vec<Score>> = BTreeMap::new();
+
+    for score in
+        search(search_terms, search_artist, search_title, scores_directory)
+    {
+        let Some(ly_file) = get_score_ly_file(&score.display().to_string())
+        else {
+            continue;
+        };
+        let name = |path: Option<&Path>| {
+            path.and_then(|path| path.file_name())
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default()
+        };
+
+        artists
+            .entry(name(score.parent()))
+            .or_default()
+            .push(Score {
+                title: name(Some(&score)),
+                ly_file: PathBuf::from(ly_file),
+                status: Status::Uncompiled,
+            });
+    }
+
+    for scores in artists.values_mut() {
+        scores.sort_by(|a, b| a.title.cmp(&b.title));
+    }
+
+    artists
+}
+
+impl App {
+    fn get_rows(&self) -> Vec<Row<'_>> {
+        let mut rows = vec![];
+
+        for (artist, scores) in &self.artists {
+            rows.push(Row::Artist(artist));
+
+            if !self.collapsed.contains(artist) {
+                rows.extend(scores.iter().map(Row::Score));
+            }
+        }
+
+        rows
+    }
+
+    fn get_selected_score(&self) -> Option<&Score> {
+        match self.get_rows().get(self.selected) {
+            Some(Row::Score(score)) => Some(score),
+            _ => None,
+        }
+    }
+
+    fn get_pdf(&self, score: &Score) -> PathBuf {
+        PathBuf::from(format!(
+            "{}/{}.pdf",
+            self.pdfs_directory,
+            score.get_stem()
+        ))
+    }
+
+    fn get_outputs(&self, score: &Score) -> Vec<PathBuf> {
+        self.options
+            .get_formats()
+            .iter()
+            .flat_map(|format| {
+                format.get_output_files(&score.ly_file, &self.pdfs_directory)
+            })
+            .collect()
+    }
+
+    fn get_status(&self, score: &Score) -> Status {
+        if self.get_outputs(score).is_empty() {
+            Status::Uncompiled
+        } else if self.options.get_formats().iter().all(|format| {
+            format.is_compiled(&score.ly_file, &self.pdfs_directory)
+        }) {
+            Status::Compiled
+        } else {
+            Status::Outdated
+        }
+    }
+
+    fn refresh_statuses(&mut self) {
+        let mut artists = take(&mut self.artists);
+
+        for score in artists.values_mut().flatten() {
+            score.status = self.get_status(score);
+        }
+
+        self.artists = artists;
+    }
+
+    fn refresh_selected_status(&mut self) {
+        let Some(score) = self.get_selected_score() else {
+            return;
+        };
+
+        let ly_file = score.ly_file.clone();
+        let status = self.get_status(score);
+
+        if let Some(score) = self
+            .artists
+            .values_mut()
+            .flatten()
+            .find(|score| score.ly_file == ly_file)
+        {
+            score.status = status;
+        }
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        let last = self.get_rows().len().saturating_sub(1);
+
+        self.selected = self.selected.saturating_add_signed(offset).min(last);
+    }
+
+    fn toggle_artist(&mut self) {
+        let artist = match self.get_rows().get(self.selected) {
+            Some(Row::Artist(artist)) => (*artist).to_string(),
+            _ => return,
+        };
+
+        if !self.collapsed.remove(&artist) {
+            self.collapsed.insert(artist);
+        }
+    }
+
+    fn compile(&mut self) {
+        let Some(score) = self.get_selected_score() else {
+            return;
+        };
+
+        if matches!(self.get_status(score), Status::Compiled) {
+            self.log = format!("{} is up to date.", score.title);
+            return;
+        }
+
+        let result = compile_input_file(
+            &score.ly_file,
+            &self.lilypond_version,
+            &Some(self.scores_directory.clone()),
+            &Some(self.pdfs_directory.clone()),
+            &self.options,
+        );
+
+        self.log = match result {
+            Ok(output) => {
+                let compiled: Vec<String> = self
+                    .get_outputs(score)
+                    .iter()
+                    .map(|output| format!("Compiled {}", output.display()))
+                    .collect();
+
+                format!("{output}{}", compiled.join("\n"))
+            }
+            Err(output) => output,
+        };
+
+        self.refresh_selected_status();
+    }
+
+    fn open(&mut self) {
+        let Some(score) = self.get_selected_score() else {
+            return;
+        };
+
+        let pdf = self.get_pdf(score);
+
+        self.log = if pdf.exists() {
+            open_in_pdf_viewer(&pdf);
+            format!("Opened {}", pdf.display())
+        } else {
+            format!("{} has not been compiled.", score.title)
+        };
+    }
+
+    fn confirm_clean(&mut self) {
+        let Some(score) = self.get_selected_score() else {
+            return;
+        };
+
+        let outputs = self.get_outputs(score);
+
+        if outputs.is_empty() {
+            self.log = format!("{} has not been compiled.", score.title);
+            return;
+        }
+
+        self.log = format!(
+            "Are you sure you want to remove {}? [y/n]",
+            outputs
+                .iter()
+                .map(|output| output.display().to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        );
+        self.confirming_clean = true;
+    }
+
+    fn clean(&mut self) {
+        let Some(score) = self.get_selected_score() else {
+            return;
+        };
+
+        self.log = self
+            .get_outputs(score)
+            .iter()
+            .map(|output| match remove_file(output) {
+                Ok(()) => format!("Removed {}", output.display()),
+                Err(err) => {
+                    format!("Failed to remove {} ({err})", output.display())
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        self.refresh_selected_status();
+    }
+
+    fn update_version(&mut self) {
+        let Some(score) = self.get_selected_score() else {
+            return;
+        };
+
+        let new_version = get_new_version(&None);
+
+        self.log = if update_file_version(&score.ly_file, &None, &new_version)
+        {
+            format!(
+                "Updated {} to lilypond {new_version}",
+                score.ly_file.display()
+            )
+        } else {
+            format!(
+                "{} is already at lilypond {new_version} or newer",
+                score.ly_file.display()
+            )
+        };
+
+        self.refresh_selected_status();
+    }
+}
+
+struct ScoreTree<'a>(&'a App);
+
+impl Draw for ScoreTree<'_> {
+    fn draw(&self, canvas: &mut dyn Canvas) -> DrawResult<()> {
+        let (_, height) = canvas.size()?;
+        let app = self.0;
+        let offset = (app.selected + 1).saturating_sub(height);
+
+        for (row, item) in
+            app.get_rows().iter().enumerate().skip(offset).take(height)
+        {
+            let selected = if row == app.selected {
+                Effect::REVERSE
+            } else {
+                Effect::empty()
+            };
+            let line = row - offset;
+
+            match item {
+                Row::Artist(artist) => {
+                    let fold = if app.collapsed.contains(*artist) {
+                        "▸"
+                    } else {
+                        "▾"
+                    };
+
+                    canvas.print_with_attr(
+                        line,
+                        0,
+                        &format!("{fold} {artist}"),
+                        Attr::from(Color::BLUE)
+                            .effect(Effect::BOLD | selected),
+                    )?;
+                }
+                Row::Score(score) => {
+                    let (symbol, color) = score.status.get_symbol();
+
+                    canvas.print_with_attr(line, 2, symbol, color.into())?;
+                    canvas.print_with_attr(
+                        line,
+                        4,
+                        &score.title,
+                        Attr::default().effect(selected),
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Widget for ScoreTree<'_> {}
+
+struct Metadata<'a>(&'a App);
+
+impl Draw for Metadata<'_> {
+    fn draw(&self, canvas: &mut dyn Canvas) -> DrawResult<()> {
+        let app = self.0;
+
+        let Some(score) = app.get_selected_score() else {
+            return Ok(());
+        };
+
+        let metadata =
+            get_composition_metadata(&score.ly_file.display().to_string());
+        let fields = [
+            ("Title", metadata.title),
+            ("Subtitle", metadata.subtitle),
+            ("Composer", metadata.composer),
+            ("Arranger", metadata.arranger),
+            ("Key", metadata.key),
+            ("Time", metadata.time),
+            ("LilyPond version", metadata.lilypond_version),
+            (
+                "Instrumentation",
+                Some(metadata.instruments.join(", "))
+                    .filter(|instruments| !instruments.is_empty()),
+            ),
+            ("Status", Some(score.status.get_description().to_string())),
+            ("Score", Some(score.ly_file.display().to_string())),
+            ("PDF", Some(app.get_pdf(score).display().to_string())),
+        ];
+
+        for (line, (key, value)) in fields
+            .iter()
+            .filter_map(|(key, value)| {
+                value.as_ref().map(|value| (key, value))
+            })
+            .enumerate()
+        {
+            let column = canvas.print_with_attr(
+                line,
+                0,
+                &format!("{key}: "),
+                Color::YELLOW.into(),
+            )?;
+
+            canvas.print(line, column, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Widget for Metadata<'_> {}
+
+struct Log<'a>(&'a str);
+
+impl Draw for Log<'_> {
+    fn draw(&self, canvas: &mut dyn Canvas) -> DrawResult<()> {
+        let (_, height) = canvas.size()?;
+        let lines: Vec<&str> = self.0.lines().collect();
+
+        for (line, text) in lines
+            .iter()
+            .skip(lines.len().saturating_sub(height))
+            .enumerate()
+        {
+            canvas.print(line, 0, text)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Widget for Log<'_> {}
+
+struct Help;
+
+impl Draw for Help {
+    fn draw(&self, canvas: &mut dyn Canvas) -> DrawResult<()> {
+        canvas.print_with_attr(0, 0, HELP, Effect::DIM.into())?;
+
+        Ok(())
+    }
+}
+
+impl Widget for Help {}
+
+fn draw(term: &Term, app: &App) {
+    let layout = VSplit::default()
+        .split(
+            HSplit::default()
+                .split(
+                    Win::new(ScoreTree(app))
+                        .title("Scores")
+                        .border(true)
+                        .basis(Size::Percent(40)),
+                )
+                .split(Win::new(Metadata(app)).title("Info").border(true)),
+        )
+        .split(
+            Win::new(Log(&app.log))
+                .title("Log")
+                .border(true)
+                .basis(Size::Fixed(LOG_HEIGHT))
+                .grow(0)
+                .shrink(0),
+        )
+        .split(Win::new(Help).basis(Size::Fixed(1)).grow(0).shrink(0));
+
+    let _ = term.draw(&layout);
+    let _ = term.present();
+}
+
+fn edit(term: &Term, app: &mut App) {
+    let Some(score) = app.get_selected_score() else {
+        return;
+    };
+
+    let ly_file = score.ly_file.clone();
+
+    if uses_terminal_editor() {
+        let _ = term.pause();
+        open_in_editor(&ly_file, None);
+        let _ = term.restart();
+    } else {
+        open_in_editor(&ly_file, None);
+    }
+
+    app.log = format!("Edited {}", ly_file.display());
+    app.refresh_selected_status();
+}
+
+pub fn main(
+    search_terms: &Vec<String>,
+    search_artist: bool,
+    search_title: bool,
+    lilypond_version: &Option<String>,
+    scores_directory: &Option<String>,
+    pdfs_directory: &Option<String>,
+    options: CompileOptions<'static>,
+) {
+    let mut app = App {
+        artists: get_artists(
+            search_terms,
+            search_artist,
+            search_title,
+            scores_directory,
+        ),
+        collapsed: BTreeSet::new(),
+        selected: 0,
+        log: String::new(),
+        lilypond_version: lilypond_version.clone(),
+        scores_directory: get_scores_directory_from_arg(scores_directory),
+        pdfs_directory: get_pdfs_directory_from_arg(pdfs_directory),
+        options: CompileOptions {
+            quiet: true,
+            ..options
+        },
+        confirming_clean: false,
+    };
+
+    if app.artists.is_empty() {
+        println!("No scores found.");
+        return;
+    }
+
+    app.refresh_statuses();
+
+    let term: Term = Term::new().expect("Failed to start terminal UI.");
+
+    draw(&term, &app);
+
+    while let Ok(event) = term.poll_event() {
+        let Event::Key(key) = event else {
+            draw(&term, &app);
+            continue;
+        };
+
+        if app.confirming_clean {
+            app.confirming_clean = false;
+
+            if key == Key::Char('y') {
+                app.clean();
+            } else {
+                app.log = String::from("Cancelled.");
+            }
+
+            draw(&term, &app);
+            continue;
+        }
+
+        match key {
+            Key::Char('q') | Key::ESC | Key::Ctrl('c') => break,
+            Key::Char('j') | Key::Down => app.move_selection(1),
+            Key::Char('k') | Key::Up => app.move_selection(-1),
+            Key::PageDown => app.move_selection(LOG_HEIGHT as isize),
+            Key::PageUp => app.move_selection(-(LOG_HEIGHT as isize)),
+            Key::Enter | Key::Char(' ') => app.toggle_artist(),
+            Key::Char('c') if app.get_selected_score().is_some() => {
+                app.log = String::from("Compiling...");
+                draw(&term, &app);
+                app.compile();
+            }
+            Key::Char('o') => app.open(),
+            Key::Char('e') => edit(&term, &mut app),
+            Key::Char('d') => app.confirm_clean(),
+            Key::Char('u') => app.update_version(),
+            Key::Char('r') => {
+                app.artists = get_artists(
+                    search_terms,
+                    search_artist,
+                    search_title,
+                    scores_directory,
+                );
+                app.refresh_statuses();
+                app.move_selection(0);
+            }
+            _ => {}
+        }
+
+        draw(&term, &app);
+    }
+}
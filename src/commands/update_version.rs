@@ -1,7 +1,7 @@
 use std::cmp::Ordering;
 use std::fs::{remove_file, rename, File};
 use std::io::{BufRead, BufReader, Write as IoWrite};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use human_sort::compare;
 
@@ -9,21 +9,22 @@ use super::lilypond::global::get_global_version;
 use super::scores::{get_found_ly_files, get_selected_items};
 use crate::commands::received_confirmation;
 
-fn get_new_version(version: &Option<String>) -> String {
+pub fn get_new_version(version: &Option<String>) -> String {
     version
         .as_ref()
         .map_or_else(get_global_version, ToOwned::to_owned)
 }
 
-fn is_outdated(version: &str, new_version: &str) -> bool {
+pub fn is_outdated(version: &str, new_version: &str) -> bool {
     compare(version, new_version) == Ordering::Less
 }
 
-fn update_version(
-    file: PathBuf,
+/// Rewrites the `\version` of `file`, returning whether it changed.
+pub fn update_file_version(
+    file: &Path,
     version: &Option<String>,
-    new_version: &String,
-) {
+    new_version: &str,
+) -> bool {
     let output_file = format!(
         "/tmp/{}",
         file.file_name()
@@ -39,7 +40,7 @@ fn update_version(
     });
     let mut changed = false;
 
-    for line in BufReader::new(File::open(&file).unwrap_or_else(|err| {
+    for line in BufReader::new(File::open(file).unwrap_or_else(|err| {
         panic!(
             "{}",
             format!("Failed to open file {} ({})", &file.display(), err)
@@ -62,11 +63,6 @@ fn update_version(
             if version.is_some() || is_outdated(&file_version, new_version) {
                 line = line.replace(&file_version, new_version);
                 changed = true;
-                println!(
-                    "Updated {} to lilypond {}",
-                    &file.display(),
-                    &new_version
-                );
             }
         }
 
@@ -79,6 +75,14 @@ fn update_version(
     } else {
         remove_file(output_file).ok();
     }
+
+    changed
+}
+
+fn update_version(file: PathBuf, version: &Option<String>, new_version: &str) {
+    if update_file_version(&file, version, new_version) {
+        println!("Updated {} to lilypond {}", file.display(), new_version);
+    }
 }
 
 pub fn main(
@@ -0,0 +1,247 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::create_dir_all;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::offset::Local;
+use owo_colors::OwoColorize;
+use watchexec::Watchexec;
+use watchexec_events::filekind::{FileEventKind, ModifyKind};
+use watchexec_events::{Event, Tag};
+use watchexec_signals::Signal;
+
+use super::compile::{compile_input_file, CompileOptions};
+use super::includes::{get_dependencies, get_dependent_scores};
+use super::scores::{get_score_ly_file, search};
+use super::{get_pdfs_directory_from_arg, get_scores_directory_from_arg};
+
+/// Rapid saves within this window are compiled once.
+const DEBOUNCE_MILLISECONDS: u64 = 300;
+
+/// The directories of `scores` and everything they include, so that
+/// includes added while watching are picked up as well.
+fn get_watched_directories(
+    scores: &[PathBuf],
+    scores_directory: &str,
+) -> BTreeSet<PathBuf> {
+    scores
+        .iter()
+        .flat_map(|score| get_dependencies(score, scores_directory))
+        .filter_map(|file| file.parent().map(Path::to_path_buf))
+        .collect()
+}
+
+fn is_content_change(event: &Event) -> bool {
+    event.tags.iter().any(|tag| {
+        matches!(
+            tag,
+            Tag::FileEventKind(
+                FileEventKind::Create(_)
+                    | FileEventKind::Modify(
+                        ModifyKind::Data(_) | ModifyKind::Name(_)
+                    )
+            )
+        )
+    })
+}
+
+/// Watches `scores` and their includes, calling `on_change` with the scores
+/// affected by each batch of changes.
+#[tokio::main]
+pub async fn watch_scores(
+    scores: Vec<PathBuf>,
+    scores_directory: String,
+    on_change: impl Fn(Vec<PathBuf>) + Send + Sync + 'static,
+) -> eyre::Result<()> {
+    let watched_directories =
+        get_watched_directories(&scores, &scores_directory);
+
+    let watchexec = Watchexec::new(move |mut action| {
+        let changed_files: Vec<PathBuf> = action
+            .events
+            .iter()
+            .filter(|event| is_content_change(event))
+            .flat_map(|event| {
+                event.paths().map(|(path, _)| path.to_path_buf())
+            })
+            .collect();
+
+        let changed_scores =
+            get_dependent_scores(&changed_files, &scores, &scores_directory);
+
+        if !changed_scores.is_empty() {
+            on_change(changed_scores);
+        }
+
+        if action.signals().any(|signal| signal == Signal::Interrupt) {
+            action.quit();
+        }
+
+        action
+    })?;
+
+    watchexec.config.pathset(watched_directories);
+    watchexec
+        .config
+        .throttle(Duration::from_millis(DEBOUNCE_MILLISECONDS));
+    let _ = watchexec.main().await?;
+
+    Ok(())
+}
+
+fn get_score_name(score: &Path) -> String {
+    score
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn get_first_error(output: &str) -> String {
+    output
+        .lines()
+        .find(|line| line.contains("error:"))
+        .or_else(|| output.lines().find(|line| !line.trim().is_empty()))
+        .unwrap_or("Unknown error")
+        .trim()
+        .to_string()
+}
+
+fn print_error_summary(errors: &BTreeMap<String, String>) {
+    if errors.is_empty() {
+        return;
+    }
+
+    let count = errors.len();
+    let scores = if count == 1 { "score" } else { "scores" };
+    let names = errors.keys().cloned().collect::<Vec<String>>().join(", ");
+
+    println!("{}", format!("  {count} {scores} failing: {names}").red());
+}
+
+struct Rebuilder {
+    lilypond_version: Option<String>,
+    scores_directory: String,
+    pdfs_directory: String,
+    options: CompileOptions<'static>,
+    errors: Mutex<BTreeMap<String, String>>,
+}
+
+impl Rebuilder {
+    /// Compiles `score`, printing a one-line status.
+    fn rebuild(&self, score: &Path) {
+        let name = get_score_name(score);
+        let time = Local::now().format("%H:%M:%S");
+        let start = Instant::now();
+        let result = compile_input_file(
+            score,
+            &self.lilypond_version,
+            &Some(self.scores_directory.clone()),
+            &Some(self.pdfs_directory.clone()),
+            &self.options,
+        );
+        let seconds = start.elapsed().as_secs_f32();
+        let mut errors = self.errors.lock().expect("Failed to read errors.");
+
+        match result {
+            Ok(_) => {
+                println!("[{time}] {} {name} ({seconds:.1}s)", "✓".green());
+                errors.remove(&name);
+            }
+            Err(output) => {
+                let error = get_first_error(&output);
+
+                println!("[{time}] {} {name}: {error}", "✗".red());
+                errors.insert(name, error);
+            }
+        }
+    }
+
+    fn is_out_of_date(&self, score: &Path) -> bool {
+        self.options
+            .get_formats()
+            .iter()
+            .any(|format| !format.is_compiled(score, &self.pdfs_directory))
+    }
+
+    fn print_error_summary(&self) {
+        print_error_summary(
+            &self.errors.lock().expect("Failed to read errors."),
+        );
+    }
+}
+
+fn get_scores(
+    search_terms: &Vec<String>,
+    search_artist: bool,
+    search_title: bool,
+    scores_directory: &Option<String>,
+) -> Vec<PathBuf> {
+    search(search_terms, search_artist, search_title, scores_directory)
+        .iter()
+        .filter_map(|score| score.to_str())
+        .filter_map(|score| get_score_ly_file(&score.to_string()))
+        .map(PathBuf::from)
+        .collect()
+}
+
+pub fn main(
+    search_terms: &Vec<String>,
+    search_artist: bool,
+    search_title: bool,
+    lilypond_version: &Option<String>,
+    scores_directory: &Option<String>,
+    pdfs_directory: &Option<String>,
+    options: CompileOptions<'static>,
+) {
+    let scores = get_scores(
+        search_terms,
+        search_artist,
+        search_title,
+        scores_directory,
+    );
+
+    if scores.is_empty() {
+        println!("No scores found.");
+        return;
+    }
+
+    let rebuilder = Rebuilder {
+        lilypond_version: lilypond_version.clone(),
+        scores_directory: get_scores_directory_from_arg(scores_directory),
+        pdfs_directory: get_pdfs_directory_from_arg(pdfs_directory),
+        options: CompileOptions {
+            force: true,
+            quiet: true,
+            ..options
+        },
+        errors: Mutex::new(BTreeMap::new()),
+    };
+
+    create_dir_all(&rebuilder.pdfs_directory)
+        .expect("Failed to create pdfs directory.");
+
+    for score in &scores {
+        if rebuilder.is_out_of_date(score) {
+            rebuilder.rebuild(score);
+        }
+    }
+
+    rebuilder.print_error_summary();
+
+    let count = scores.len();
+    let noun = if count == 1 { "score" } else { "scores" };
+    println!("Watching {count} {noun} for changes...");
+
+    let scores_directory = rebuilder.scores_directory.clone();
+
+    watch_scores(scores, scores_directory, move |scores| {
+        for score in &scores {
+            rebuilder.rebuild(score);
+        }
+
+        rebuilder.print_error_summary();
+    })
+    .expect("Failed to watch scores.");
+}
@@ -1,7 +1,6 @@
 use std::{
     fs::{create_dir_all, read_to_string, write},
     path::Path,
-    process::Command,
 };
 
 use owo_colors::OwoColorize;
@@ -10,8 +9,9 @@ use shellexpand::tilde;
 use toml::{from_str, to_string};
 use users::get_current_username;
 
+use crate::commands::launcher::open_in_editor;
 use crate::commands::table;
-use crate::commands::{templates::Template, ConfigKey};
+use crate::commands::{templates::Template, ConfigKey, OutputFormat};
 
 static CONFIG_PATH: &str = "~/.config/thoth/config.toml";
 
@@ -22,6 +22,16 @@ struct ConfigFile {
     pdfs_directory: Option<String>,
     template: Option<Template>,
     instrument: Option<String>,
+    key: Option<String>,
+    time: Option<String>,
+    tempo: Option<String>,
+    clef: Option<String>,
+    pickup: Option<String>,
+    paper: Option<String>,
+    editor: Option<String>,
+    pdf_viewer: Option<String>,
+    point_and_click: Option<bool>,
+    editor_is_terminal: Option<bool>,
 }
 
 impl ConfigFile {
@@ -32,6 +42,16 @@ impl ConfigFile {
             pdfs_directory: Some(config.pdfs_directory),
             template: Some(config.template),
             instrument: Some(config.instrument),
+            key: Some(config.key),
+            time: Some(config.time),
+            tempo: config.tempo,
+            clef: Some(config.clef),
+            pickup: config.pickup,
+            paper: Some(config.paper),
+            editor: config.editor,
+            pdf_viewer: config.pdf_viewer,
+            point_and_click: Some(config.point_and_click),
+            editor_is_terminal: config.editor_is_terminal,
         }
     }
 }
@@ -87,6 +107,22 @@ fn get_default_instrument() -> String {
     "Instrument".to_string()
 }
 
+fn get_default_key() -> String {
+    "c major".to_string()
+}
+
+fn get_default_time() -> String {
+    "4/4".to_string()
+}
+
+fn get_default_clef() -> String {
+    "treble".to_string()
+}
+
+fn get_default_paper() -> String {
+    "letter".to_string()
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Config {
     pub composer: String,
@@ -94,6 +130,17 @@ pub struct Config {
     pub pdfs_directory: String,
     pub template: Template,
     pub instrument: String,
+    pub key: String,
+    pub time: String,
+    pub tempo: Option<String>,
+    pub clef: String,
+    pub pickup: Option<String>,
+    pub paper: String,
+    pub editor: Option<String>,
+    pub pdf_viewer: Option<String>,
+    pub point_and_click: bool,
+    /// Overrides guessing whether the editor runs in the terminal.
+    pub editor_is_terminal: Option<bool>,
 }
 
 impl Default for Config {
@@ -112,20 +159,20 @@ impl Default for Config {
             pdfs_directory: get_default_pdfs_directory(),
             template: get_default_template(),
             instrument: get_default_instrument(),
+            key: get_default_key(),
+            time: get_default_time(),
+            tempo: None,
+            clef: get_default_clef(),
+            pickup: None,
+            paper: get_default_paper(),
+            editor: None,
+            pdf_viewer: None,
+            point_and_click: false,
+            editor_is_terminal: None,
         }
     }
 }
 
-fn get_template_from_string(value: &str) -> Option<Template> {
-    match value {
-        "form" => Some(Template::Form),
-        "lead" => Some(Template::Lead),
-        "piano" => Some(Template::Piano),
-        "single" => Some(Template::Single),
-        _ => None,
-    }
-}
-
 impl Config {
     pub fn from_config_file() -> Self {
         let config_file = load_config_file();
@@ -170,6 +217,16 @@ impl Config {
             pdfs_directory,
             template,
             instrument,
+            key: config_file.key.unwrap_or_else(get_default_key),
+            time: config_file.time.unwrap_or_else(get_default_time),
+            tempo: config_file.tempo,
+            clef: config_file.clef.unwrap_or_else(get_default_clef),
+            pickup: config_file.pickup,
+            paper: config_file.paper.unwrap_or_else(get_default_paper),
+            editor: config_file.editor,
+            pdf_viewer: config_file.pdf_viewer,
+            point_and_click: config_file.point_and_click.unwrap_or_default(),
+            editor_is_terminal: config_file.editor_is_terminal,
         }
     }
 
@@ -205,7 +262,7 @@ impl Config {
         vec![Self::style_key(key), Self::style_value(value)]
     }
 
-    pub fn display() {
+    pub fn display(format: &Option<OutputFormat>) {
         let config = Self::from_config_file();
 
         let rows = vec![
@@ -223,9 +280,41 @@ impl Config {
                 "template",
                 config.template.to_string().as_str(),
             ),
+            Self::style_key_value("key", config.key.as_str()),
+            Self::style_key_value("time", config.time.as_str()),
+            Self::style_key_value(
+                "tempo",
+                config.tempo.unwrap_or_default().as_str(),
+            ),
+            Self::style_key_value("clef", config.clef.as_str()),
+            Self::style_key_value(
+                "pickup",
+                config.pickup.unwrap_or_default().as_str(),
+            ),
+            Self::style_key_value("paper", config.paper.as_str()),
+            Self::style_key_value(
+                "editor",
+                config.editor.unwrap_or_default().as_str(),
+            ),
+            Self::style_key_value(
+                "pdf_viewer",
+                config.pdf_viewer.unwrap_or_default().as_str(),
+            ),
+            Self::style_key_value(
+                "point_and_click",
+                config.point_and_click.to_string().as_str(),
+            ),
+            Self::style_key_value(
+                "editor_is_terminal",
+                config
+                    .editor_is_terminal
+                    .map(|value| value.to_string())
+                    .unwrap_or_default()
+                    .as_str(),
+            ),
         ];
 
-        table::print(&[], rows);
+        table::print(&[], rows, format);
     }
 
     pub fn display_path() {
@@ -233,10 +322,7 @@ impl Config {
     }
 
     pub fn edit() {
-        Command::new("open")
-            .arg(get_config_path())
-            .output()
-            .expect("Failed to open config path.");
+        open_in_editor(Path::new(&get_config_path()), None);
     }
 
     pub fn display_value(key: &ConfigKey) {
@@ -252,13 +338,57 @@ impl Config {
                 println!("{}", Self::get_pdfs_directory());
             }
             "template" => {
-                println!("{:?}", Self::get_template());
+                println!("{}", Self::get_template());
+            }
+            "key" => println!("{}", Self::from_config_file().key),
+            "time" => println!("{}", Self::from_config_file().time),
+            "tempo" => {
+                println!(
+                    "{}",
+                    Self::from_config_file().tempo.unwrap_or_default()
+                );
+            }
+            "clef" => println!("{}", Self::from_config_file().clef),
+            "pickup" => {
+                println!(
+                    "{}",
+                    Self::from_config_file().pickup.unwrap_or_default()
+                );
+            }
+            "paper" => println!("{}", Self::from_config_file().paper),
+            "editor" => {
+                println!(
+                    "{}",
+                    Self::from_config_file().editor.unwrap_or_default()
+                );
+            }
+            "pdf_viewer" => {
+                println!(
+                    "{}",
+                    Self::from_config_file().pdf_viewer.unwrap_or_default()
+                );
+            }
+            "point_and_click" => {
+                println!("{}", Self::from_config_file().point_and_click);
+            }
+            "editor_is_terminal" => {
+                println!(
+                    "{}",
+                    Self::from_config_file()
+                        .editor_is_terminal
+                        .map(|value| value.to_string())
+                        .unwrap_or_default()
+                );
             }
             _ => println!("\"{key}\" is not a recognized config key"),
         };
     }
 
-    pub fn set_value(key: &ConfigKey, value: String) {
+    pub fn set_value(
+        key: &ConfigKey,
+        value: String,
+        format: &Option<OutputFormat>,
+    ) {
         let mut config = Self::from_config_file();
 
         match key {
@@ -266,12 +396,42 @@ impl Config {
             ConfigKey::ScoresDirectory => config.scores_directory = value,
             ConfigKey::PDFSDirectory => config.pdfs_directory = value,
             ConfigKey::Template => {
-                let value = get_template_from_string(&value);
-                if let Some(template) = value {
-                    config.template = template;
+                match value.parse::<Template>().and_then(|template| {
+                    template.check_exists(&None).map(|()| template)
+                }) {
+                    Ok(template) => config.template = template,
+                    Err(message) => {
+                        println!("{message}");
+                        return;
+                    }
                 }
             }
             ConfigKey::Instrument => config.instrument = value,
+            ConfigKey::Key => config.key = value,
+            ConfigKey::Time => config.time = value,
+            ConfigKey::Tempo => {
+                config.tempo = Some(value).filter(|value| !value.is_empty());
+            }
+            ConfigKey::Clef => config.clef = value,
+            ConfigKey::Pickup => {
+                config.pickup = Some(value).filter(|value| !value.is_empty());
+            }
+            ConfigKey::Paper => config.paper = value,
+            ConfigKey::Editor => {
+                config.editor = Some(value).filter(|value| !value.is_empty());
+            }
+            ConfigKey::PdfViewer => {
+                config.pdf_viewer =
+                    Some(value).filter(|value| !value.is_empty());
+            }
+            ConfigKey::PointAndClick => {
+                if let Ok(point_and_click) = value.parse() {
+                    config.point_and_click = point_and_click;
+                }
+            }
+            ConfigKey::EditorIsTerminal => {
+                config.editor_is_terminal = value.parse().ok();
+            }
         };
 
         let contents = to_string(&ConfigFile::from_config(config))
@@ -279,6 +439,6 @@ impl Config {
         let config_path_name = get_config_path();
         let config_path = Path::new(&config_path_name);
         write(config_path, contents).expect("Unable to write config.");
-        Self::display();
+        Self::display(format);
     }
 }
@@ -6,20 +6,31 @@ use std::println;
 use clap::Parser;
 use commands::activate;
 use commands::clean;
-use commands::compile;
+use commands::compile::{self, CompileOptions};
 use commands::config as config_command;
-use commands::create::{self, ScoreFileSettings};
-use commands::edit;
+use commands::create::{self, CreateOptions, ScoreFileSettings};
+use commands::edit::{self, EditOptions};
+use commands::export;
 use commands::helpers;
+use commands::import;
 use commands::info;
 use commands::lilypond;
-use commands::list;
+use commands::list::{self, ListOptions};
+use commands::lsp;
 use commands::open;
+use commands::parts;
+use commands::preview;
+use commands::render;
 use commands::sketch;
+use commands::stats;
 use commands::templates;
+use commands::textedit;
+use commands::transpose::{self, Transposition};
+use commands::tui;
 use commands::update_path;
 use commands::update_version;
-use commands::Command;
+use commands::watch;
+use commands::{Command, CreateArgs};
 
 #[derive(Parser)]
 #[command(about, long_about = None)]
@@ -31,11 +42,122 @@ struct Cli {
 }
 
 fn main() {
+    color_eyre::install().expect("Failed to install error reporting.");
+
     match &Cli::parse().command {
         Some(Command::Activate { shell }) => activate::main(shell),
         Some(Command::UpdatePath { shell, version }) => {
             update_path::main(shell, version);
         }
+        Some(Command::Parts {
+            search_term,
+            artist,
+            title,
+            scores_directory,
+            pdfs_directory,
+        }) => parts::main(
+            search_term,
+            *artist,
+            *title,
+            scores_directory,
+            pdfs_directory,
+        ),
+        Some(Command::Preview {
+            search_term,
+            artist,
+            title,
+            port,
+            lilypond_version,
+            scores_directory,
+        }) => preview::main(
+            search_term,
+            *artist,
+            *title,
+            port,
+            lilypond_version,
+            scores_directory,
+        ),
+        Some(Command::Lsp {
+            lilypond_version,
+            scores_directory,
+        }) => lsp::main(lilypond_version, scores_directory),
+        Some(Command::Render {
+            fragment,
+            protocol,
+            lilypond_version,
+            scores_directory,
+        }) => render::main(
+            fragment,
+            protocol,
+            lilypond_version,
+            scores_directory,
+        ),
+        Some(Command::Textedit { uri }) => textedit::main(uri),
+        Some(Command::Transpose {
+            search_term,
+            to,
+            instrument,
+            artist,
+            title,
+            force,
+            scores_directory,
+            pdfs_directory,
+        }) => transpose::main(
+            search_term,
+            *artist,
+            *title,
+            &Transposition {
+                to: to.clone(),
+                instrument: instrument.clone(),
+            },
+            *force,
+            scores_directory,
+            pdfs_directory,
+        ),
+        Some(Command::Tui {
+            search_terms,
+            artist,
+            title,
+            lilypond_version,
+            scores_directory,
+            pdfs_directory,
+            format,
+            resolution,
+        }) => tui::main(
+            search_terms,
+            *artist,
+            *title,
+            lilypond_version,
+            scores_directory,
+            pdfs_directory,
+            CompileOptions {
+                formats: format.clone(),
+                resolution: *resolution,
+                ..Default::default()
+            },
+        ),
+        Some(Command::Watch {
+            search_terms,
+            artist,
+            title,
+            lilypond_version,
+            scores_directory,
+            pdfs_directory,
+            format,
+            resolution,
+        }) => watch::main(
+            search_terms,
+            *artist,
+            *title,
+            lilypond_version,
+            scores_directory,
+            pdfs_directory,
+            CompileOptions {
+                formats: format.clone(),
+                resolution: *resolution,
+                ..Default::default()
+            },
+        ),
         Some(Command::UpdateVersion {
             search_terms,
             version,
@@ -56,6 +178,7 @@ fn main() {
             artist,
             title,
             all,
+            format,
             scores_directory,
             pdfs_directory,
         }) => clean::main(
@@ -63,6 +186,7 @@ fn main() {
             *artist,
             *title,
             *all,
+            format,
             scores_directory,
             pdfs_directory,
         ),
@@ -75,6 +199,8 @@ fn main() {
             scores_directory,
             pdfs_directory,
             force,
+            format,
+            resolution,
         }) => compile::main(
             search_terms,
             *artist,
@@ -83,39 +209,89 @@ fn main() {
             lilypond_version,
             scores_directory,
             pdfs_directory,
-            *force,
+            &CompileOptions {
+                formats: format.clone(),
+                resolution: *resolution,
+                force: *force,
+                ..Default::default()
+            },
         ),
         Some(Command::Config {
             edit,
             path,
             key,
             set,
-        }) => config_command::main(*edit, *path, key, set),
-        Some(Command::Create {
-            title,
-            subtitle,
-            composer,
-            arranger,
-            instrument,
-            template,
-            edit,
-            lilypond_version,
-            scores_directory,
-            pdfs_directory,
-        }) => {
+            format,
+        }) => config_command::main(*edit, *path, key, set, format),
+        Some(Command::Create(args)) => {
+            let CreateArgs {
+                title,
+                subtitle,
+                composer,
+                arranger,
+                instrument,
+                template,
+                key,
+                time,
+                tempo,
+                clef,
+                pickup,
+                paper,
+                edit,
+                force,
+                dry_run,
+                lilypond_version,
+                scores_directory,
+                pdfs_directory,
+            } = args.as_ref();
+
+            if let Some(Err(message)) = template
+                .as_ref()
+                .map(|template| template.check_exists(scores_directory))
+            {
+                println!("{message}");
+                return;
+            }
+
             let settings = ScoreFileSettings {
-                title: title.to_owned(),
+                title: title.clone().unwrap_or_default(),
                 subtitle: subtitle.to_owned(),
                 composer: composer.to_owned(),
                 arranger: arranger.to_owned(),
                 template: template.to_owned(),
                 instrument: instrument.to_owned(),
+                key: key.to_owned(),
+                time: time.to_owned(),
+                tempo: tempo.to_owned(),
+                clef: clef.to_owned(),
+                pickup: pickup.to_owned(),
+                paper: paper.to_owned(),
+            };
+
+            let mut options = CreateOptions {
+                edit: *edit,
+                is_sketch: false,
+                force: *force,
+                dry_run: *dry_run,
+            };
+
+            let settings = if title.is_some() {
+                settings
+            } else {
+                let Some(settings) = create::wizard::main(
+                    settings,
+                    &mut options,
+                    scores_directory,
+                ) else {
+                    return;
+                };
+
+                settings
             };
 
             create::main(
                 &settings,
-                *edit,
-                false,
+                &options,
                 lilypond_version,
                 scores_directory,
                 pdfs_directory,
@@ -126,6 +302,7 @@ fn main() {
             artist,
             title,
             all,
+            preview,
             lilypond_version,
             scores_directory,
             pdfs_directory,
@@ -135,20 +312,64 @@ fn main() {
                 *artist,
                 *title,
                 *all,
-                false,
+                &EditOptions {
+                    preview: *preview,
+                    ..Default::default()
+                },
                 lilypond_version,
                 scores_directory,
                 pdfs_directory,
             );
         }
+        Some(Command::Export {
+            search_term,
+            artist,
+            title,
+            chordpro,
+            text: _,
+            output,
+            scores_directory,
+        }) => export::main(
+            search_term,
+            *artist,
+            *title,
+            *chordpro,
+            output,
+            scores_directory,
+        ),
+        Some(Command::Import {
+            file,
+            chordpro,
+            title,
+            composer,
+            force,
+            lilypond_version,
+            scores_directory,
+        }) => import::main(
+            file,
+            title,
+            composer,
+            *chordpro,
+            *force,
+            lilypond_version,
+            scores_directory,
+        ),
         Some(Command::Info {
             search_term,
             artist,
             title,
             all,
+            preview,
             scores_directory,
         }) => {
-            info::main(search_term, *artist, *title, *all, scores_directory);
+            info::main(
+                search_term,
+                *artist,
+                *title,
+                *all,
+                *preview,
+                scores_directory,
+            );
         }
         Some(Command::Lilypond { version, command }) => {
             lilypond::main(version, command);
@@ -161,15 +382,19 @@ fn main() {
             title,
             scores_directory,
             pdfs_directory,
+            format,
         }) => {
             list::main(
                 search_terms,
-                *outdated,
-                *compiled,
-                *artist,
-                *title,
+                &ListOptions {
+                    outdated: *outdated,
+                    compiled: *compiled,
+                    search_artist: *artist,
+                    search_title: *title,
+                },
                 scores_directory,
                 pdfs_directory,
+                format,
             );
         }
         Some(Command::Open {
@@ -191,11 +416,33 @@ fn main() {
                 pdfs_directory,
             );
         }
-        Some(Command::Templates { command }) => templates::main(command),
-        Some(Command::Helpers { command }) => helpers::main(command),
-        Some(Command::Sketch { lilypond_version }) => {
-            sketch::main(lilypond_version);
+        Some(Command::Templates { command, format }) => {
+            templates::main(command, format);
         }
+        Some(Command::Helpers { command, format }) => {
+            helpers::main(command, format);
+        }
+        Some(Command::Sketch {
+            command,
+            template,
+            last,
+            lilypond_version,
+        }) => {
+            sketch::main(command, template, *last, lilypond_version);
+        }
+        Some(Command::Stats {
+            search_terms,
+            artist,
+            title,
+            scores_directory,
+            pdfs_directory,
+        }) => stats::main(
+            search_terms,
+            *artist,
+            *title,
+            scores_directory,
+            pdfs_directory,
+        ),
         _ => {
             println!("Please choose a command.");
         }